@@ -88,4 +88,160 @@ pub enum WagerError {
 
     #[msg("Game is not in progress")]
     GameNotInProgress,
+
+    #[msg("Player profile does not belong to the player")]
+    InvalidPlayerProfile,
+
+    #[msg("Minimum rating of the band is above its maximum")]
+    InvalidRatingBand,
+
+    #[msg("Player rating is outside the rating band of the session")]
+    RatingOutOfBand,
+
+    #[msg("Spawn purchases must grant spawns and cost a non-zero price")]
+    InvalidSpawnConfig,
+
+    #[msg("Player reached the maximum number of spawn purchases")]
+    SpawnPurchaseLimitReached,
+
+    #[msg("Kills must carry a non-zero weight in the payout score")]
+    InvalidPayoutConfig,
+
+    #[msg("Remainder token account is missing or not the configured destination")]
+    InvalidRemainderAccount,
+
+    #[msg("Kill batch is empty or too large")]
+    InvalidKillBatch,
+
+    #[msg("Kill event players do not match its kind")]
+    InvalidKillEvent,
+
+    #[msg("Game session does not commit a match log")]
+    MatchLogNotEnabled,
+
+    #[msg("Match log was already finalized")]
+    MatchLogFinalized,
+
+    #[msg("Match log commits cannot cover fewer events than the previous one")]
+    InvalidMatchLogCommit,
+
+    #[msg("Match log must be finalized before settlement")]
+    MatchLogNotFinalized,
+
+    #[msg("Side bets are only taken on winner takes all sessions before they start")]
+    SideBetsClosed,
+
+    #[msg("Players of the session cannot place side bets on it")]
+    PlayerCannotSideBet,
+
+    #[msg("Side bet must stake a non-zero amount on the same team as earlier bets")]
+    InvalidSideBet,
+
+    #[msg("Side bets are not settled yet")]
+    SideBetsNotSettled,
+
+    #[msg("Tournament id must be between 1 and 6 bytes long")]
+    InvalidTournamentId,
+
+    #[msg("Bracket size must be a power of two between 2 and 16")]
+    InvalidBracketSize,
+
+    #[msg("Player already entered the tournament")]
+    AlreadyEntered,
+
+    #[msg("Bracket match does not exist")]
+    InvalidBracketMatch,
+
+    #[msg("Players of the bracket match are not decided yet")]
+    BracketMatchNotReady,
+
+    #[msg("Winner of the bracket match already advanced")]
+    BracketMatchAlreadyAdvanced,
+
+    #[msg("Champion token account is missing or not owned by the champion")]
+    InvalidChampionTokenAccount,
+
+    #[msg("Series must be an odd number of rounds up to 9 in a winner takes all mode")]
+    InvalidSeriesLength,
+
+    #[msg("Game session does not play a series")]
+    SeriesNotEnabled,
+
+    #[msg("Series was already decided")]
+    SeriesAlreadyDecided,
+
+    #[msg("Series must be decided in favor of the winning team before settlement")]
+    SeriesNotDecided,
+
+    #[msg("Lobby template id, fee, timeout or bet is invalid")]
+    InvalidTemplate,
+
+    #[msg("Lobby template created all the sessions its counter allows")]
+    TemplateExhausted,
+
+    #[msg("Game session no longer accepts players")]
+    JoinTimeoutExpired,
+
+    #[msg("Session id must be at most 10 bytes long")]
+    InvalidSessionId,
+
+    #[msg("Session of the bracket match was already created")]
+    BracketMatchAlreadyCreated,
+
+    #[msg("Account is not a legacy game session")]
+    InvalidLegacySession,
+
+    #[msg("Session authority or delegate is invalid")]
+    InvalidSessionAuthority,
+
+    #[msg("Session already has the maximum number of delegates")]
+    TooManyDelegates,
+
+    #[msg("Program is paused")]
+    ProgramPaused,
+
+    #[msg("Only the config admin or guardian can do this")]
+    UnauthorizedConfig,
+
+    #[msg("Game session is frozen")]
+    SessionFrozen,
+
+    #[msg("Bet amount is zero, outside the allowed limits or too large for the pot")]
+    InvalidBetAmount,
+
+    #[msg("Bet limits must have a non-zero minimum no larger than the maximum")]
+    InvalidBetLimits,
+
+    #[msg("Player already joined the session")]
+    PlayerAlreadyJoined,
+
+    #[msg("Player occupies several slots of the session")]
+    AmbiguousPlayer,
+
+    #[msg("Vault balance does not cover the payouts")]
+    InsufficientVaultBalance,
+
+    #[msg("Vault can only be swept to the configured treasury")]
+    InvalidSweepDestination,
+
+    #[msg("Teams do not have the minimum number of players to start")]
+    NotEnoughPlayers,
+
+    #[msg("Player forfeited the match")]
+    PlayerForfeited,
+
+    #[msg("Only the session authority or the player can forfeit a slot")]
+    UnauthorizedForfeit,
+
+    #[msg("Side bettors of the session cannot join it")]
+    BettorCannotJoin,
+
+    #[msg("Vault still holds player deposits that were not refunded or paid out")]
+    UnsettledDeposits,
+
+    #[msg("Team has no active players left to win the match")]
+    NoActivePlayers,
+
+    #[msg("Match was forfeited to the other team")]
+    MatchForfeitedToOtherTeam,
 }
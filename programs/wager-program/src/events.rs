@@ -0,0 +1,94 @@
+//! Events emitted by the program
+use crate::state::KillEvent;
+use anchor_lang::prelude::*;
+
+/// Kills applied to a game session by the game server
+#[event]
+pub struct KillsRecorded {
+    pub game_session: Pubkey,  // Game session the kills belong to
+    pub kills: Vec<KillEvent>, // Kills in the order they were applied
+    pub timestamp: i64,        // Time the kills were recorded
+}
+
+/// Root of the off-chain match log committed by the game server
+#[event]
+pub struct MatchLogCommitted {
+    pub game_session: Pubkey, // Game session the log belongs to
+    pub root: [u8; 32],       // Merkle root of the events logged so far
+    pub event_count: u64,     // Number of events covered by the root
+    pub is_final: bool,       // Whether the root covers the whole match
+    pub timestamp: i64,       // Time the root was committed
+}
+
+/// Winner of a round of a best-of-N series
+#[event]
+pub struct SeriesRoundRecorded {
+    pub game_session: Pubkey,      // Game session playing the series
+    pub round: u8,                 // Index of the round in the series
+    pub winning_team: u8,          // Team that won the round
+    pub series_winner: Option<u8>, // Team that clinched the series, if any
+    pub timestamp: i64,            // Time the round was recorded
+}
+
+/// Game session handed to another game server
+#[event]
+pub struct SessionAuthorityTransferred {
+    pub game_session: Pubkey,       // Game session that changed hands
+    pub previous_authority: Pubkey, // Game server that ran the session
+    pub new_authority: Pubkey,      // Game server now running the session
+    pub timestamp: i64,             // Time of the transfer
+}
+
+/// Program paused or resumed through the global config
+#[event]
+pub struct PauseChanged {
+    pub authority: Pubkey, // Admin or guardian that flipped the flag
+    pub paused: bool,      // Whether the program is now paused
+    pub timestamp: i64,    // Time of the change
+}
+
+/// Game session frozen or unfrozen by the config admin
+#[event]
+pub struct SessionFreezeChanged {
+    pub game_session: Pubkey, // Game session that was frozen or unfrozen
+    pub frozen: bool,         // Whether the session is now frozen
+    pub timestamp: i64,       // Time of the change
+}
+
+/// Leftovers of a completed session vault swept out
+#[event]
+pub struct VaultSwept {
+    pub game_session: Pubkey, // Completed game session the vault belonged to
+    pub destination: Pubkey,  // Token account that received the leftovers
+    pub amount: u64,          // Amount swept out of the vault
+    pub timestamp: i64,       // Time of the sweep
+}
+
+/// Lobby started by its game server before every slot was filled
+#[event]
+pub struct SessionStarted {
+    pub game_session: Pubkey,  // Game session that started
+    pub team_size: u8,         // Players on each team once balanced
+    pub no_shows: Vec<Pubkey>, // Players dropped, their stake stays in the vault
+    pub refunded: Vec<Pubkey>, // Extra players of the larger team, refunded their bet
+    pub timestamp: i64,        // Time the match started
+}
+
+/// Player slot forfeited by the game server or the player surrendering
+#[event]
+pub struct PlayerForfeited {
+    pub game_session: Pubkey, // Game session the player forfeited
+    pub team: u8,             // Team of the player
+    pub player: Pubkey,       // Player that forfeited
+    pub signer: Pubkey,       // Session authority or the player itself
+    pub match_ended: bool,    // Whether the forfeit handed the match to the other team
+    pub timestamp: i64,       // Time of the forfeit
+}
+
+/// Tournament cancelled by its game server, entry fees refunded
+#[event]
+pub struct TournamentCancelled {
+    pub tournament: Pubkey, // Tournament that was cancelled
+    pub refunded: u8,       // Number of entrants refunded their entry fee
+    pub timestamp: i64,     // Time of the cancellation
+}
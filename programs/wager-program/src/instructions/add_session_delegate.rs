@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+use super::SessionAuthority;
+
+pub fn add_session_delegate_handler(
+    ctx: Context<SessionAuthority>,
+    _session_index: u64,
+    delegate: Pubkey,
+) -> Result<()> {
+    ctx.accounts.game_session.add_delegate(delegate)
+}
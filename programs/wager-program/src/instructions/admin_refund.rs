@@ -0,0 +1,71 @@
+use crate::{errors::WagerError, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use super::refund_wager::transfer_refunds;
+
+pub fn admin_refund_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AdminRefund<'info>>,
+    session_index: u64,
+) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    msg!("Starting admin refund for session: {}", session_index);
+
+    // Players get back everything they paid in, spawn purchases included
+    let refunds = game_session.refunds()?;
+
+    transfer_refunds(
+        ctx.remaining_accounts,
+        &refunds,
+        game_session,
+        &ctx.accounts.vault,
+        &mut ctx.accounts.vault_token_account,
+        &ctx.accounts.token_program,
+    )?;
+
+    // Mark session as completed and refund side bets
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.release_deposits(refunds.iter().map(|(_, amount)| amount).sum())?;
+    game_session.status = GameStatus::Completed;
+    game_session.side_pool.outcome = SidePoolOutcome::Cancelled;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_index: u64)]
+pub struct AdminRefund<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = admin @ WagerError::UnauthorizedConfig,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.status != GameStatus::Completed @ WagerError::InvalidGameState,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        mut,
+        seeds = [b"vault", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = TOKEN_ID,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,118 @@
+use crate::{errors::WagerError, payout::vault_residual, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+/// Advances the winner of a settled bracket match and pays the prize pool to
+/// the champion once the final was settled
+pub fn advance_tournament_handler(
+    ctx: Context<AdvanceTournament>,
+    tournament_id: String,
+    round: u8,
+    match_index: u8,
+) -> Result<()> {
+    let session_result = &ctx.accounts.session_result;
+    let winning_team = session_result
+        .winning_team
+        .ok_or(WagerError::InvalidWinningTeam)?;
+    let winner = if winning_team == 0 {
+        session_result.team_a.players[0]
+    } else {
+        session_result.team_b.players[0]
+    };
+
+    let tournament = &mut ctx.accounts.tournament;
+    let Some(champion) = tournament.advance(round, match_index, winner)? else {
+        return Ok(());
+    };
+    msg!("Champion of {}: {}", tournament_id, champion);
+
+    let champion_token_account = ctx
+        .accounts
+        .champion_token_account
+        .as_ref()
+        .ok_or(WagerError::InvalidChampionTokenAccount)?;
+    require!(
+        champion_token_account.owner == champion,
+        WagerError::InvalidChampionTokenAccount
+    );
+
+    // The prize vault must hold the whole prize pool
+    let residual = vault_residual(
+        ctx.accounts.tournament_vault_token_account.amount,
+        &[tournament.prize_pool],
+    )?;
+
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx
+                    .accounts
+                    .tournament_vault_token_account
+                    .to_account_info(),
+                to: champion_token_account.to_account_info(),
+                authority: ctx.accounts.tournament_vault.to_account_info(),
+            },
+            &[&[
+                b"tournament_vault",
+                tournament.key().as_ref(),
+                &[tournament.vault_bump],
+            ]],
+        ),
+        tournament.prize_pool,
+    )?;
+
+    ctx.accounts.tournament_vault_token_account.reload()?;
+    require!(
+        ctx.accounts.tournament_vault_token_account.amount == residual,
+        WagerError::IncompleteDistribution
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(tournament_id: String, round: u8, match_index: u8)]
+pub struct AdvanceTournament<'info> {
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", game_server.key().as_ref(), tournament_id.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    /// Session the tournament created for the bracket match
+    #[account(
+        constraint = game_session.key() == tournament.match_sessions[tournament.match_number(round, match_index)] @ WagerError::InvalidBracketMatch,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(has_one = game_session @ WagerError::InvalidBracketMatch)]
+    pub session_result: Account<'info, SessionResult>,
+
+    /// CHECK: PDA owning the prize pool tokens
+    #[account(
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump,
+    )]
+    pub tournament_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = TOKEN_ID,
+        associated_token::authority = tournament_vault,
+    )]
+    pub tournament_vault_token_account: Account<'info, TokenAccount>,
+
+    /// Receives the prize pool when the final is advanced
+    #[account(
+        mut,
+        constraint = champion_token_account.mint == TOKEN_ID @ WagerError::InvalidTokenMint,
+    )]
+    pub champion_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
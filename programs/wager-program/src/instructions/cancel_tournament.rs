@@ -0,0 +1,69 @@
+use crate::{errors::WagerError, events::TournamentCancelled, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use super::refund_wager::transfer_refunds_from;
+
+/// Refunds every entrant of a tournament that never filled its bracket or
+/// whose bracket cannot be played out
+pub fn cancel_tournament_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelTournament<'info>>,
+    tournament_id: String,
+) -> Result<()> {
+    msg!("Cancelling tournament: {}", tournament_id);
+
+    let refunds = ctx.accounts.tournament.cancel()?;
+    if !refunds.is_empty() {
+        let tournament = &ctx.accounts.tournament;
+        transfer_refunds_from(
+            ctx.remaining_accounts,
+            &refunds,
+            &ctx.accounts.tournament_vault,
+            &[
+                b"tournament_vault",
+                tournament.key().as_ref(),
+                &[tournament.vault_bump],
+            ],
+            &mut ctx.accounts.tournament_vault_token_account,
+            &ctx.accounts.token_program,
+        )?;
+    }
+
+    emit!(TournamentCancelled {
+        tournament: ctx.accounts.tournament.key(),
+        refunded: refunds.len() as u8,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(tournament_id: String)]
+pub struct CancelTournament<'info> {
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", game_server.key().as_ref(), tournament_id.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    /// CHECK: PDA owning the prize pool tokens
+    #[account(
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump,
+    )]
+    pub tournament_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = TOKEN_ID,
+        associated_token::authority = tournament_vault,
+    )]
+    pub tournament_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
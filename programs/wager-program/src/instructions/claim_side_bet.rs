@@ -0,0 +1,91 @@
+use crate::{errors::WagerError, payout::vault_residual, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+pub fn claim_side_bet_handler(ctx: Context<ClaimSideBet>, _session_index: u64) -> Result<()> {
+    let side_bet = &ctx.accounts.side_bet;
+
+    // Fails until the session was settled or refunded
+    let payout = ctx
+        .accounts
+        .game_session
+        .side_pool
+        .payout(side_bet.team, side_bet.amount)?;
+    msg!("Side bet payout for {}: {}", side_bet.bettor, payout);
+
+    // Losing bets are only closed
+    if payout > 0 {
+        let residual = vault_residual(ctx.accounts.side_vault_token_account.amount, &[payout])?;
+
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.side_vault_token_account.to_account_info(),
+                    to: ctx.accounts.bettor_token_account.to_account_info(),
+                    authority: ctx.accounts.side_vault.to_account_info(),
+                },
+                &[&[
+                    b"side_vault",
+                    ctx.accounts.game_session.key().as_ref(),
+                    &[ctx.bumps.side_vault],
+                ]],
+            ),
+            payout,
+        )?;
+
+        ctx.accounts.side_vault_token_account.reload()?;
+        require!(
+            ctx.accounts.side_vault_token_account.amount == residual,
+            WagerError::IncompleteDistribution
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_index: u64)]
+pub struct ClaimSideBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        close = bettor,
+        seeds = [b"side_bet", game_session.key().as_ref(), bettor.key().as_ref()],
+        bump = side_bet.bump,
+        has_one = bettor,
+        has_one = game_session,
+    )]
+    pub side_bet: Account<'info, SideBet>,
+
+    #[account(
+        mut,
+        constraint = bettor_token_account.owner == bettor.key(),
+        constraint = bettor_token_account.mint == TOKEN_ID
+    )]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA owning the side bet tokens, kept apart from the player vault
+    #[account(
+        seeds = [b"side_vault", game_session.key().as_ref()],
+        bump
+    )]
+    pub side_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = TOKEN_ID,
+        associated_token::authority = side_vault,
+    )]
+    pub side_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
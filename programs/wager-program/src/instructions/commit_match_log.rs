@@ -0,0 +1,31 @@
+use crate::{errors::WagerError, events::MatchLogCommitted};
+use anchor_lang::prelude::*;
+
+use super::RecordKill;
+
+pub fn commit_match_log_handler(
+    ctx: Context<RecordKill>,
+    _session_index: u64,
+    root: [u8; 32],
+    event_count: u64,
+    is_final: bool,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    // The final root gates settlement, so delegates cannot commit roots
+    require!(
+        game_session.authority == ctx.accounts.game_server.key(),
+        WagerError::UnauthorizedDistribution
+    );
+    game_session.commit_match_log(root, event_count, is_final)?;
+
+    emit!(MatchLogCommitted {
+        game_session: game_session.key(),
+        root,
+        event_count,
+        is_final,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -5,16 +5,52 @@ use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount};
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_game_session_handler(
     ctx: Context<CreateGameSession>,
-    session_id: String,
+    session_id: Option<String>,
     bet_amount: u64,
     game_mode: GameMode,
+    rating_band: Option<RatingBand>,
+    spawn_config: Option<SpawnConfig>,
+    payout_config: Option<PayoutConfig>,
+    commit_match_log: bool,
+    best_of: Option<u8>,
 ) -> Result<()> {
+    let session_id = session_id.unwrap_or_default();
+    validate_session_id(&session_id)?;
+    ctx.accounts.global_config.bet_limits.check(bet_amount)?;
+
+    if let Some(band) = rating_band {
+        require!(
+            band.min_rating <= band.max_rating,
+            WagerError::InvalidRatingBand
+        );
+    }
+
+    let spawn_config = spawn_config.unwrap_or(SpawnConfig::from_session_bet(bet_amount));
+    spawn_config.validate()?;
+    let payout_config = payout_config.unwrap_or_default();
+    payout_config.validate()?;
+
+    // Pay-to-spawn sessions have no winning team to play a series for
+    let series = best_of.map(Series::new).transpose()?;
+    require!(
+        series.is_none() || !game_mode.is_pay_to_spawn(),
+        WagerError::InvalidSeriesLength
+    );
+
+    let session_index = ctx
+        .accounts
+        .session_counter
+        .claim_index(ctx.accounts.game_server.key(), ctx.bumps.session_counter)?;
+
     let clock = Clock::get()?;
     let game_session = &mut ctx.accounts.game_session;
 
     game_session.session_id = session_id;
+    game_session.session_index = session_index;
+    game_session.creator = ctx.accounts.game_server.key();
     game_session.authority = ctx.accounts.game_server.key();
     game_session.session_bet = bet_amount;
     game_session.game_mode = game_mode;
@@ -22,9 +58,15 @@ pub fn create_game_session_handler(
     game_session.created_at = clock.unix_timestamp;
     game_session.bump = ctx.bumps.game_session;
     game_session.vault_bump = ctx.bumps.vault;
+    game_session.rating_band = rating_band;
+    game_session.spawn_config = spawn_config;
+    game_session.payout_config = payout_config;
+    game_session.match_log = commit_match_log.then(MatchLog::default);
+    game_session.series = series;
+    game_session.validate_stakes()?;
 
     // Log all the accounts
-    msg!("Game session: {}", game_session.key());
+    msg!("Game session {}: {}", session_index, game_session.key());
     msg!("Vault: {}", ctx.accounts.vault.key());
     msg!(
         "Vault token account: {}",
@@ -34,16 +76,24 @@ pub fn create_game_session_handler(
 }
 
 #[derive(Accounts)]
-#[instruction(session_id: String)]
 pub struct CreateGameSession<'info> {
     #[account(mut)]
     pub game_server: Signer<'info>,
 
+    #[account(
+        init_if_needed,
+        payer = game_server,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"session_counter", game_server.key().as_ref()],
+        bump
+    )]
+    pub session_counter: Account<'info, SessionCounter>,
+
     #[account(
         init,
         payer = game_server,
-        space = 8 + 4 + 10 + 32 + 8 + 1 + (2 * (32 * 5 + 16 * 5 + 16 * 5 + 8)) + 1 + 8 + 1 + 1 + 1,
-        seeds = [b"game_session", session_id.as_bytes()],
+        space = GameSession::SPACE,
+        seeds = [b"game_session", game_server.key().as_ref(), &session_counter.next_index.to_le_bytes()],
         bump
     )]
     pub game_session: Account<'info, GameSession>,
@@ -53,7 +103,7 @@ pub struct CreateGameSession<'info> {
         init,
         payer = game_server,
         space = 0,
-        seeds = [b"vault", session_id.as_bytes()],
+        seeds = [b"vault", game_server.key().as_ref(), &session_counter.next_index.to_le_bytes()],
         bump
     )]
     pub vault: AccountInfo<'info>,
@@ -66,14 +116,17 @@ pub struct CreateGameSession<'info> {
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
-    #[account(
-        mut,
-        address = TOKEN_ID @ WagerError::InvalidMint
-    )]
+    #[account(address = TOKEN_ID @ WagerError::InvalidMint)]
     pub mint: Account<'info, anchor_spl::token::Mint>,
 
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 }
@@ -0,0 +1,55 @@
+use crate::{errors::WagerError, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+pub fn create_lobby_template_handler(
+    ctx: Context<CreateLobbyTemplate>,
+    template_id: String,
+    game_mode: GameMode,
+    session_bet: u64,
+    fee_bps: u16,
+    join_timeout: i64,
+    bet_limits: Option<BetLimits>,
+) -> Result<()> {
+    ctx.accounts.global_config.bet_limits.check(session_bet)?;
+
+    let lobby_template = &mut ctx.accounts.lobby_template;
+    lobby_template.template_id = template_id;
+    lobby_template.authority = ctx.accounts.game_server.key();
+    lobby_template.game_mode = game_mode;
+    lobby_template.session_bet = session_bet;
+    lobby_template.mint = ctx.accounts.mint.key();
+    lobby_template.fee_bps = fee_bps;
+    lobby_template.join_timeout = join_timeout;
+    lobby_template.next_session_index = 0;
+    lobby_template.bet_limits = bet_limits.unwrap_or(BetLimits::exactly(session_bet));
+    lobby_template.bump = ctx.bumps.lobby_template;
+    lobby_template.validate()?;
+
+    msg!("Lobby template: {}", lobby_template.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: String)]
+pub struct CreateLobbyTemplate<'info> {
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+
+    #[account(
+        init,
+        payer = game_server,
+        space = 8 + (4 + MAX_TEMPLATE_ID_LEN) + 32 + 1 + 8 + 32 + 2 + 8 + 8 + 16 + 1,
+        seeds = [b"lobby_template", game_server.key().as_ref(), template_id.as_bytes()],
+        bump
+    )]
+    pub lobby_template: Account<'info, LobbyTemplate>,
+
+    #[account(seeds = [b"global_config"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(address = TOKEN_ID @ WagerError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
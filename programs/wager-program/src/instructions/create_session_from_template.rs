@@ -0,0 +1,122 @@
+use crate::{errors::WagerError, state::*};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Creates the next session of a lobby template, its human-readable id is
+/// derived from the template counter
+pub fn create_session_from_template_handler(
+    ctx: Context<CreateSessionFromTemplate>,
+    _template_id: String,
+    bet_amount: Option<u64>,
+) -> Result<()> {
+    let lobby_template = &mut ctx.accounts.lobby_template;
+
+    // Sessions may pick any bet inside both the template and the program limits
+    let session_bet = bet_amount.unwrap_or(lobby_template.session_bet);
+    lobby_template.bet_limits.check(session_bet)?;
+    ctx.accounts.global_config.bet_limits.check(session_bet)?;
+
+    let session_id = lobby_template.next_session_id();
+    lobby_template.advance_session_index()?;
+    let session_index = ctx
+        .accounts
+        .session_counter
+        .claim_index(ctx.accounts.game_server.key(), ctx.bumps.session_counter)?;
+
+    let clock = Clock::get()?;
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.session_id = session_id;
+    game_session.session_index = session_index;
+    game_session.creator = ctx.accounts.game_server.key();
+    game_session.authority = lobby_template.authority;
+    game_session.session_bet = session_bet;
+    game_session.game_mode = lobby_template.game_mode;
+    game_session.status = GameStatus::WaitingForPlayers;
+    game_session.created_at = clock.unix_timestamp;
+    game_session.bump = ctx.bumps.game_session;
+    game_session.vault_bump = ctx.bumps.vault;
+    game_session.spawn_config = SpawnConfig::from_session_bet(session_bet);
+    game_session.payout_config = PayoutConfig::default();
+    game_session.fee_bps = lobby_template.fee_bps;
+    game_session.validate_stakes()?;
+    if lobby_template.join_timeout > 0 {
+        game_session.join_deadline = clock
+            .unix_timestamp
+            .checked_add(lobby_template.join_timeout)
+            .ok_or(WagerError::ArithmeticError)?;
+    }
+
+    msg!(
+        "Game session {}: {}",
+        game_session.session_id,
+        game_session.key()
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: String)]
+pub struct CreateSessionFromTemplate<'info> {
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lobby_template", game_server.key().as_ref(), template_id.as_bytes()],
+        bump = lobby_template.bump,
+        has_one = mint,
+        constraint = lobby_template.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+    )]
+    pub lobby_template: Account<'info, LobbyTemplate>,
+
+    #[account(
+        init_if_needed,
+        payer = game_server,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"session_counter", game_server.key().as_ref()],
+        bump
+    )]
+    pub session_counter: Account<'info, SessionCounter>,
+
+    #[account(
+        init,
+        payer = game_server,
+        space = GameSession::SPACE,
+        seeds = [b"game_session", game_server.key().as_ref(), &session_counter.next_index.to_le_bytes()],
+        bump
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: This is safe as it's just used to store SOL
+    #[account(
+        init,
+        payer = game_server,
+        space = 0,
+        seeds = [b"vault", game_server.key().as_ref(), &session_counter.next_index.to_le_bytes()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = game_server,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
@@ -0,0 +1,75 @@
+use crate::{errors::WagerError, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+pub fn create_tournament_handler(
+    ctx: Context<CreateTournament>,
+    tournament_id: String,
+    entry_fee: u64,
+    bracket_size: u8,
+) -> Result<()> {
+    Tournament::validate(
+        &tournament_id,
+        entry_fee,
+        bracket_size,
+        &ctx.accounts.global_config.bet_limits,
+    )?;
+
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.tournament_id = tournament_id;
+    tournament.authority = ctx.accounts.game_server.key();
+    tournament.entry_fee = entry_fee;
+    tournament.bracket_size = bracket_size;
+    tournament.status = TournamentStatus::Registering;
+    tournament.bump = ctx.bumps.tournament;
+    tournament.vault_bump = ctx.bumps.tournament_vault;
+
+    msg!("Tournament: {}", tournament.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(tournament_id: String)]
+pub struct CreateTournament<'info> {
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+
+    #[account(
+        init,
+        payer = game_server,
+        space = 8 + (4 + MAX_TOURNAMENT_ID_LEN) + 32 + 8 + 1 + 1 + 32 * (2 * MAX_TOURNAMENT_PLAYERS - 1) + 32 * (MAX_TOURNAMENT_PLAYERS - 1) + 1 + 8 + 1 + 1,
+        seeds = [b"tournament", game_server.key().as_ref(), tournament_id.as_bytes()],
+        bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    /// CHECK: PDA owning the prize pool tokens
+    #[account(
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub tournament_vault: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = game_server,
+        associated_token::mint = mint,
+        associated_token::authority = tournament_vault,
+    )]
+    pub tournament_vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(address = TOKEN_ID @ WagerError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
@@ -0,0 +1,104 @@
+use crate::{errors::WagerError, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+/// Creates the 1v1 session of a bracket match with both players already
+/// seated. Entry fees went to the prize pool, so the session has no bet and
+/// starts right away.
+pub fn create_tournament_match_handler(
+    ctx: Context<CreateTournamentMatch>,
+    _tournament_id: String,
+    round: u8,
+    match_index: u8,
+) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    let (player_a, player_b) = tournament.match_players(round, match_index)?;
+    tournament.set_match_session(round, match_index, ctx.accounts.game_session.key())?;
+
+    let session_index = ctx
+        .accounts
+        .session_counter
+        .claim_index(ctx.accounts.game_server.key(), ctx.bumps.session_counter)?;
+
+    let clock = Clock::get()?;
+    let game_session = &mut ctx.accounts.game_session;
+
+    game_session.session_id = tournament.match_session_id(round, match_index);
+    game_session.session_index = session_index;
+    game_session.creator = ctx.accounts.game_server.key();
+    game_session.authority = tournament.authority;
+    game_session.session_bet = 0;
+    game_session.game_mode = GameMode::WinnerTakesAllOneVsOne;
+    game_session.team_a.players[0] = player_a;
+    game_session.team_a.player_spawns[0] = INITIAL_SPAWNS;
+    game_session.team_b.players[0] = player_b;
+    game_session.team_b.player_spawns[0] = INITIAL_SPAWNS;
+    game_session.status = GameStatus::InProgress;
+    game_session.created_at = clock.unix_timestamp;
+    game_session.bump = ctx.bumps.game_session;
+    game_session.vault_bump = ctx.bumps.vault;
+    game_session.spawn_config = SpawnConfig::from_session_bet(0);
+    game_session.payout_config = PayoutConfig::default();
+
+    msg!("Game session {}: {}", session_index, game_session.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(tournament_id: String)]
+pub struct CreateTournamentMatch<'info> {
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", game_server.key().as_ref(), tournament_id.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        init_if_needed,
+        payer = game_server,
+        space = 8 + 32 + 8 + 1,
+        seeds = [b"session_counter", game_server.key().as_ref()],
+        bump
+    )]
+    pub session_counter: Account<'info, SessionCounter>,
+
+    #[account(
+        init,
+        payer = game_server,
+        space = GameSession::SPACE,
+        seeds = [b"game_session", game_server.key().as_ref(), &session_counter.next_index.to_le_bytes()],
+        bump
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: This is safe as it's just used to store SOL
+    #[account(
+        init,
+        payer = game_server,
+        space = 0,
+        seeds = [b"vault", game_server.key().as_ref(), &session_counter.next_index.to_le_bytes()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = game_server,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(address = TOKEN_ID @ WagerError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,55 +1,93 @@
-use crate::{errors::WagerError, state::*, TOKEN_ID};
+use crate::payout::{split_pot, vault_residual};
+use crate::rating::team_average_rating;
+use crate::{errors::WagerError, state::*, utils::is_paired, TOKEN_ID};
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount};
 
 pub fn distribute_pay_spawn_earnings<'info>(
     ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
-    session_id: String,
+    session_index: u64,
 ) -> Result<()> {
     let game_session = &ctx.accounts.game_session;
-    msg!("Starting distribution for session: {}", session_id);
+    msg!("Starting distribution for session: {}", session_index);
+
+    // Snapshot the final scoreboard before paying out
+    let session_result = &mut ctx.accounts.session_result;
+    session_result.record_scoreboard(game_session.key(), game_session, None);
 
-    let players = game_session.get_all_players();
-    msg!("Number of players: {}", players.len());
     msg!(
         "Number of remaining accounts: {}",
         ctx.remaining_accounts.len()
     );
 
+    let (payout_accounts, profile_accounts) =
+        split_player_profiles(ctx.remaining_accounts, game_session)?;
+
     // We need at least one player and their token account
     require!(
-        !ctx.remaining_accounts.is_empty(),
+        !payout_accounts.is_empty(),
         WagerError::InvalidRemainingAccounts
     );
 
     // Make sure remaining accounts are in pairs
     require!(
-        ctx.remaining_accounts.len() % 2 == 0,
+        is_paired(payout_accounts),
         WagerError::InvalidRemainingAccounts
     );
 
-    for player in players {
-        // Skip players with no kills/spawns
-        let kills_and_spawns = game_session.get_kills_and_spawns(player)?;
-        if kills_and_spawns == 0 {
+    // The whole vault is the pot: entry stakes plus every spawn purchase, less the session fee
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    let fee = game_session.fee_on(vault_balance);
+    let pot = vault_balance
+        .checked_sub(fee)
+        .ok_or(WagerError::ArithmeticError)?;
+    // Forfeited players keep their score on the scoreboard but share nothing
+    let joined_players = game_session.get_active_players();
+    let scores = joined_players
+        .iter()
+        .map(|(team, slot, _)| game_session.get_player_score(*team, *slot))
+        .collect::<Result<Vec<u64>>>()?;
+    let (mut payouts, remainder) = split_pot(pot, &scores);
+    msg!("Pot: {}, rounding remainder: {}", pot, remainder);
+
+    // Without a configured destination the remainder goes to the top scorer
+    let remainder_destination = game_session.payout_config.remainder_destination;
+    if remainder_destination.is_none() && remainder > 0 {
+        let top_scorer = scores
+            .iter()
+            .enumerate()
+            .max_by_key(|(i, score)| (**score, std::cmp::Reverse(*i)))
+            .map(|(i, _)| i)
+            .ok_or(WagerError::NoWinnersFound)?;
+        payouts[top_scorer] = payouts[top_scorer]
+            .checked_add(remainder)
+            .ok_or(WagerError::ArithmeticError)?;
+    }
+
+    // Make sure the vault covers every payout before the first transfer
+    let mut outgoing = payouts.clone();
+    if remainder_destination.is_some() {
+        outgoing.push(remainder);
+    }
+    let residual = vault_residual(vault_balance, &outgoing)?;
+
+    for ((_, _, player), earnings) in joined_players.into_iter().zip(payouts) {
+        // Skip players who earned nothing
+        if earnings == 0 {
             continue;
         }
-
-        let earnings = kills_and_spawns as u64 * game_session.session_bet / 10;
         msg!("Earnings for player {}: {}", player, earnings);
 
         // Find the player's account and token account in remaining_accounts
-        let player_index = ctx
-            .remaining_accounts
+        let player_index = payout_accounts
             .iter()
             .step_by(2) // Skip token accounts to only look at player accounts
             .position(|acc| acc.key() == player)
             .ok_or(WagerError::InvalidPlayer)?;
 
         // Get player and token account from remaining accounts
-        let player_account = &ctx.remaining_accounts[player_index * 2];
-        let player_token_account_info = &ctx.remaining_accounts[player_index * 2 + 1];
+        let player_account = &payout_accounts[player_index * 2];
+        let player_token_account_info = &payout_accounts[player_index * 2 + 1];
         let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)?;
 
         // Verify player token account constraints
@@ -64,45 +102,81 @@ pub fn distribute_pay_spawn_earnings<'info>(
             WagerError::InvalidTokenMint
         );
 
-        // Get vault balance before transfer
-        let vault_balance = ctx.accounts.vault_token_account.amount;
-        msg!("Vault balance before transfer: {}", vault_balance);
-
-        if earnings > 0 {
-            // Transfer tokens from vault to player
-            anchor_spl::token::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    anchor_spl::token::Transfer {
-                        from: ctx.accounts.vault_token_account.to_account_info(),
-                        to: player_token_account_info.to_account_info(),
-                        authority: ctx.accounts.vault.to_account_info(),
-                    },
-                    &[&[
-                        b"vault",
-                        session_id.as_bytes(),
-                        &[ctx.accounts.game_session.vault_bump],
-                    ]],
-                ),
-                earnings,
-            )?;
-        }
+        // Transfer tokens from vault to player
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: player_token_account_info.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[&[
+                    b"vault",
+                    ctx.accounts.game_session.creator.as_ref(),
+                    &session_index.to_le_bytes(),
+                    &[ctx.accounts.game_session.vault_bump],
+                ]],
+            ),
+            earnings,
+        )?;
+        session_result.record_payout(player, earnings)?;
     }
 
-    // Mark session as completed
-    let game_session = &mut ctx.accounts.game_session;
-    game_session.status = GameStatus::Completed;
+    if remainder_destination.is_some() && remainder > 0 {
+        let remainder_token_account = ctx
+            .accounts
+            .remainder_token_account
+            .as_ref()
+            .ok_or(WagerError::InvalidRemainderAccount)?;
+
+        // Transfer the rounding remainder to the configured destination
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: remainder_token_account.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[&[
+                    b"vault",
+                    ctx.accounts.game_session.creator.as_ref(),
+                    &session_index.to_le_bytes(),
+                    &[ctx.accounts.game_session.vault_bump],
+                ]],
+            ),
+            remainder,
+        )?;
+    }
+
+    // Every token of the pot must have left the vault, only the fee stays
+    ctx.accounts.vault_token_account.reload()?;
+    require!(
+        residual == fee && ctx.accounts.vault_token_account.amount == residual,
+        WagerError::IncompleteDistribution
+    );
+
+    finalize_session_result(
+        session_result,
+        ctx.accounts.vault_token_account.amount,
+        ctx.bumps.session_result,
+    )?;
+    update_player_profiles(profile_accounts, game_session, session_result)?;
+
+    // Mark session as completed, the whole vault was the pot
+    ctx.accounts.game_session.complete_settlement();
 
     Ok(())
 }
 
 pub fn distribute_all_winnings_handler<'info>(
     ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
-    session_id: String,
+    session_index: u64,
     winning_team: u8,
 ) -> Result<()> {
     let game_session = &ctx.accounts.game_session;
-    msg!("Starting distribution for session: {}", session_id);
+    msg!("Starting distribution for session: {}", session_index);
 
     // Verify authority
     require!(
@@ -116,29 +190,37 @@ pub fn distribute_all_winnings_handler<'info>(
         WagerError::InvalidWinningTeam
     );
 
-    let players_per_team = game_session.game_mode.players_per_team();
+    // Forfeits and series decide which team may be paid out, its active
+    // players share every deposit held by the vault
+    let winner_payouts = game_session.winner_payouts(winning_team)?;
 
-    // Get the winning team
-    let winning_players = if winning_team == 0 {
-        &game_session.team_a.players[0..players_per_team]
-    } else {
-        &game_session.team_b.players[0..players_per_team]
-    };
+    // Snapshot the final scoreboard before paying out
+    let session_result = &mut ctx.accounts.session_result;
+    session_result.record_scoreboard(game_session.key(), game_session, Some(winning_team));
 
-    for player in winning_players {
-        msg!("Winning player: {}", player);
+    for (player, payout) in &winner_payouts {
+        msg!("Winning player: {}, payout: {}", player, payout);
     }
 
+    let (payout_accounts, profile_accounts) =
+        split_player_profiles(ctx.remaining_accounts, game_session)?;
+
     // Get winner account and token account from remaining accounts
     require!(
-        ctx.remaining_accounts.len() >= 2 * players_per_team,
+        payout_accounts.len() >= 2 * winner_payouts.len(),
         WagerError::InvalidRemainingAccounts
     );
 
-    for i in 0..players_per_team {
+    // Make sure the vault covers every winner before the first transfer
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    msg!("Vault balance before transfer: {}", vault_balance);
+    let amounts: Vec<u64> = winner_payouts.iter().map(|(_, payout)| *payout).collect();
+    let residual = vault_residual(vault_balance, &amounts)?;
+
+    for (i, (winning_player, winning_amount)) in winner_payouts.iter().enumerate() {
         // Get winner and winner token account
-        let winner = &ctx.remaining_accounts[i * 2];
-        let winner_token_account_info = &ctx.remaining_accounts[i * 2 + 1];
+        let winner = &payout_accounts[i * 2];
+        let winner_token_account_info = &payout_accounts[i * 2 + 1];
         let winner_token_account = Account::<TokenAccount>::try_from(winner_token_account_info)?;
 
         // Verify winner constraints
@@ -153,26 +235,9 @@ pub fn distribute_all_winnings_handler<'info>(
             WagerError::InvalidTokenMint
         );
 
-        // Verify winner is actually in the winning team
+        // Winners come in team slot order so no player can be paid twice
         let winner_pubkey = winner.key();
-        require!(
-            winning_players
-                .iter()
-                .take(players_per_team)
-                .any(|&p| p == winner_pubkey),
-            WagerError::InvalidWinner
-        );
-
-        // Get vault balance before transfer
-        let vault_balance = ctx.accounts.vault_token_account.amount;
-        msg!("Vault balance before transfer: {}", vault_balance);
-
-        // Calculate total pot (sum of both teams' bets)
-        let total_pot = game_session.session_bet * players_per_team as u64 * 2;
-        msg!("Total pot calculated: {}", total_pot);
-
-        let winning_amount = game_session.session_bet * 2;
-        msg!("Winning amount calculated: {}", winning_amount);
+        require!(winner_pubkey == *winning_player, WagerError::InvalidWinner);
 
         // Transfer tokens from vault to winner
         anchor_spl::token::transfer(
@@ -185,39 +250,142 @@ pub fn distribute_all_winnings_handler<'info>(
                 },
                 &[&[
                     b"vault",
-                    session_id.as_bytes(),
+                    ctx.accounts.game_session.creator.as_ref(),
+                    &session_index.to_le_bytes(),
                     &[ctx.accounts.game_session.vault_bump],
                 ]],
             ),
-            winning_amount,
+            *winning_amount,
         )?;
+        session_result.record_payout(winner_pubkey, *winning_amount)?;
     }
 
-    // Mark session as completed
+    // The vault keeps exactly what the winners were not paid
+    ctx.accounts.vault_token_account.reload()?;
+    require!(
+        ctx.accounts.vault_token_account.amount == residual,
+        WagerError::IncompleteDistribution
+    );
+
+    finalize_session_result(
+        session_result,
+        ctx.accounts.vault_token_account.amount,
+        ctx.bumps.session_result,
+    )?;
+    update_player_profiles(profile_accounts, game_session, session_result)?;
+
+    // Mark session as completed and settle side bets against the same result
     let game_session = &mut ctx.accounts.game_session;
-    game_session.status = GameStatus::Completed;
+    game_session.complete_settlement();
+    game_session.side_pool.outcome = SidePoolOutcome::TeamWon(winning_team);
 
     Ok(())
 }
 
+/// Splits the remaining accounts into payout pairs and the trailing profiles,
+/// one profile per joined player in team order
+fn split_player_profiles<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    game_session: &GameSession,
+) -> Result<(&'info [AccountInfo<'info>], &'info [AccountInfo<'info>])> {
+    let joined_players = game_session
+        .get_all_players()
+        .iter()
+        .filter(|player| **player != Pubkey::default())
+        .count();
+    require!(
+        remaining_accounts.len() >= joined_players,
+        WagerError::InvalidRemainingAccounts
+    );
+    Ok(remaining_accounts.split_at(remaining_accounts.len() - joined_players))
+}
+
+/// Adds the settled session to the lifetime stats of every joined player and,
+/// when the session has a winning team, moves ratings by the team averages
+fn update_player_profiles<'info>(
+    profile_accounts: &'info [AccountInfo<'info>],
+    game_session: &GameSession,
+    session_result: &SessionResult,
+) -> Result<()> {
+    let mut profile_accounts = profile_accounts.iter();
+    let teams = [(0u8, &game_session.team_a), (1u8, &game_session.team_b)];
+
+    // Load every profile first, the rating change needs both team averages
+    let mut profiles = Vec::new();
+    for (team_index, team) in teams {
+        for (slot, player) in team.players.iter().enumerate() {
+            if *player == Pubkey::default() {
+                continue;
+            }
+
+            let profile_info = profile_accounts
+                .next()
+                .ok_or(WagerError::InvalidRemainingAccounts)?;
+            let profile = Account::<PlayerProfile>::try_from(profile_info)?;
+            require!(profile.player == *player, WagerError::InvalidPlayerProfile);
+
+            profiles.push((team_index, slot, profile));
+        }
+    }
+
+    let average_rating = |team_index: u8| {
+        let ratings: Vec<u32> = profiles
+            .iter()
+            .filter(|(team, ..)| *team == team_index)
+            .map(|(.., profile)| profile.rating)
+            .collect();
+        team_average_rating(&ratings)
+    };
+    let average_ratings = [average_rating(0), average_rating(1)];
+
+    for (team_index, slot, mut profile) in profiles {
+        profile.record_session(
+            game_session,
+            session_result,
+            team_index,
+            slot,
+            average_ratings,
+        )?;
+        profile.exit(&crate::ID)?;
+    }
+
+    Ok(())
+}
+
+/// Stamps the settlement time and what the vault kept once every transfer,
+/// rounding remainder included, went out
+fn finalize_session_result(
+    session_result: &mut SessionResult,
+    residual: u64,
+    bump: u8,
+) -> Result<()> {
+    session_result.fee_taken = residual;
+    session_result.settled_at = Clock::get()?.unix_timestamp;
+    session_result.bump = bump;
+    Ok(())
+}
+
 #[derive(Accounts)]
-#[instruction(session_id: String)]
+#[instruction(session_index: u64)]
 pub struct DistributeWinnings<'info> {
     /// The game server authority that created the session
+    #[account(mut)]
     pub game_server: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"game_session", session_id.as_bytes()],
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.bump,
+        constraint = !game_session.frozen @ WagerError::SessionFrozen,
         constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+        constraint = game_session.has_final_match_log() @ WagerError::MatchLogNotFinalized,
     )]
     pub game_session: Account<'info, GameSession>,
 
     /// CHECK: Vault PDA that holds the funds
     #[account(
         mut,
-        seeds = [b"vault", session_id.as_bytes()],
+        seeds = [b"vault", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.vault_bump,
     )]
     pub vault: AccountInfo<'info>,
@@ -229,7 +397,24 @@ pub struct DistributeWinnings<'info> {
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
+    /// Receives the rounding remainder of pay-to-spawn payouts when the session configures a destination
+    #[account(
+        mut,
+        constraint = Some(remainder_token_account.key()) == game_session.payout_config.remainder_destination @ WagerError::InvalidRemainderAccount,
+        constraint = remainder_token_account.mint == TOKEN_ID @ WagerError::InvalidTokenMint,
+    )]
+    pub remainder_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Receipt of the settlement, written once per session
+    #[account(
+        init,
+        payer = game_server,
+        space = 8 + 4 + 10 + 32 + 1 + 2 + (2 * (32 * 5 + 8 * 5 + 2 * 5 + 2 * 5)) + 8 + 8 + 8 + 1,
+        seeds = [b"session_result", game_session.key().as_ref()],
+        bump
+    )]
+    pub session_result: Account<'info, SessionResult>,
+
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
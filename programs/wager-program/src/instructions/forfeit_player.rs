@@ -0,0 +1,58 @@
+use crate::{errors::WagerError, events::PlayerForfeited, state::*};
+use anchor_lang::prelude::*;
+
+pub fn forfeit_player_handler(
+    ctx: Context<ForfeitPlayer>,
+    _session_index: u64,
+    team: u8,
+    player: Pubkey,
+    end_match: bool,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    // The game server forfeits disconnected players, players may surrender themselves
+    let signer = ctx.accounts.signer.key();
+    require!(
+        signer == game_session.authority || signer == player,
+        WagerError::UnauthorizedForfeit
+    );
+    // A surrendering player may not concede the match for their teammates
+    require!(
+        !end_match || signer == game_session.authority,
+        WagerError::UnauthorizedForfeit
+    );
+
+    let match_ended = game_session.forfeit_player(team, player, end_match)?;
+
+    emit!(PlayerForfeited {
+        game_session: game_session.key(),
+        team,
+        player,
+        signer,
+        match_ended,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_index: u64)]
+pub struct ForfeitPlayer<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.bump,
+        constraint = !game_session.frozen @ WagerError::SessionFrozen,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
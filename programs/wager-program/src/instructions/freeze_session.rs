@@ -0,0 +1,39 @@
+use crate::{errors::WagerError, events::SessionFreezeChanged, state::*};
+use anchor_lang::prelude::*;
+
+pub fn freeze_session_handler(ctx: Context<FreezeSession>, _session_index: u64) -> Result<()> {
+    set_session_frozen(ctx, true)
+}
+
+pub(crate) fn set_session_frozen(ctx: Context<FreezeSession>, frozen: bool) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.set_frozen(frozen)?;
+
+    emit!(SessionFreezeChanged {
+        game_session: game_session.key(),
+        frozen,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_index: u64)]
+pub struct FreezeSession<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = admin @ WagerError::UnauthorizedConfig,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.bump,
+    )]
+    pub game_session: Account<'info, GameSession>,
+}
@@ -0,0 +1,45 @@
+use crate::{program::WagerProgram, state::*};
+use anchor_lang::prelude::*;
+
+pub fn initialize_config_handler(
+    ctx: Context<InitializeConfig>,
+    guardian: Pubkey,
+    bet_limits: BetLimits,
+) -> Result<()> {
+    bet_limits.validate()?;
+
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.admin = ctx.accounts.admin.key();
+    global_config.guardian = guardian;
+    global_config.paused = false;
+    global_config.bet_limits = bet_limits;
+    global_config.treasury = None;
+    global_config.bump = ctx.bumps.global_config;
+
+    msg!("Global config: {}", global_config.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    /// Upgrade authority of the program, becomes the config admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + 32 + 32 + 1 + 16 + 33 + 1,
+        seeds = [b"global_config"],
+        bump
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, WagerProgram>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
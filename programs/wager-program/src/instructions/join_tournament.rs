@@ -0,0 +1,86 @@
+use crate::{errors::WagerError, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+pub fn join_tournament_handler(ctx: Context<JoinTournament>, _tournament_id: String) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    let player = ctx.accounts.user.key();
+    tournament.add_entrant(player)?;
+
+    // Profiles are created on the first entry of a wallet, bracket sessions settle into them
+    let player_profile = &mut ctx.accounts.player_profile;
+    if player_profile.player == Pubkey::default() {
+        player_profile.initialize(player, ctx.bumps.player_profile);
+    }
+
+    anchor_spl::token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                to: ctx
+                    .accounts
+                    .tournament_vault_token_account
+                    .to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        tournament.entry_fee,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(tournament_id: String)]
+pub struct JoinTournament<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.authority.as_ref(), tournament_id.as_bytes()],
+        bump = tournament.bump,
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == TOKEN_ID
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 4 + 4 + 4 + 8 + 8 + 8 + 8 + 8 + 4 + 1,
+        seeds = [b"player_profile", user.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// CHECK: PDA owning the prize pool tokens
+    #[account(
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump,
+    )]
+    pub tournament_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = TOKEN_ID,
+        associated_token::authority = tournament_vault,
+    )]
+    pub tournament_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
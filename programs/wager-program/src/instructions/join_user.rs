@@ -1,9 +1,8 @@
 use crate::{errors::WagerError, state::*, TOKEN_ID};
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount};
 
-pub fn join_user_handler(ctx: Context<JoinUser>, _session_id: String, team: u8) -> Result<()> {
+pub fn join_user_handler(ctx: Context<JoinUser>, _session_index: u64, team: u8) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
 
     // Validate game status
@@ -12,14 +11,39 @@ pub fn join_user_handler(ctx: Context<JoinUser>, _session_id: String, team: u8)
         WagerError::InvalidGameState
     );
 
+    // Template sessions only accept players until their join deadline
+    require!(
+        game_session.join_deadline == 0
+            || Clock::get()?.unix_timestamp <= game_session.join_deadline,
+        WagerError::JoinTimeoutExpired
+    );
+
     // Validate team number (0 for team A, 1 for team B)
     require!(team == 0 || team == 1, WagerError::InvalidTeamSelection);
 
+    // A wallet takes a single slot of the session
+    let player = ctx.accounts.user.key();
+    game_session.check_can_join(player)?;
+
     // Check if team is full already
     let empty_index = game_session.get_player_empty_slot(team)?;
 
     let session_bet = game_session.session_bet;
 
+    // Profiles are created on the first join of a wallet
+    let player_profile = &mut ctx.accounts.player_profile;
+    if player_profile.player == Pubkey::default() {
+        player_profile.initialize(player, ctx.bumps.player_profile);
+    }
+
+    // Ranked sessions only accept players inside their rating band
+    if let Some(band) = game_session.rating_band {
+        require!(
+            band.contains(player_profile.rating),
+            WagerError::RatingOutOfBand
+        );
+    }
+
     // Transfer SPL tokens from user to vault using user's signature
     anchor_spl::token::transfer(
         CpiContext::new(
@@ -32,8 +56,7 @@ pub fn join_user_handler(ctx: Context<JoinUser>, _session_id: String, team: u8)
         ),
         session_bet,
     )?;
-
-    let player = ctx.accounts.user.key();
+    game_session.hold_deposit(session_bet)?;
 
     // Get reference to the selected team
     let selected_team = if team == 0 {
@@ -44,8 +67,13 @@ pub fn join_user_handler(ctx: Context<JoinUser>, _session_id: String, team: u8)
 
     // Add player to the first available slot
     selected_team.players[empty_index] = player.key();
-    selected_team.player_spawns[empty_index] = 10;
+    selected_team.player_spawns[empty_index] = INITIAL_SPAWNS;
     selected_team.player_kills[empty_index] = 0;
+    selected_team.player_deaths[empty_index] = 0;
+    selected_team.spawn_purchases[empty_index] = 0;
+    selected_team.player_assists[empty_index] = 0;
+    selected_team.player_team_kills[empty_index] = 0;
+    selected_team.player_forfeited[empty_index] = false;
 
     if game_session.check_all_filled()? {
         game_session.status = GameStatus::InProgress;
@@ -55,18 +83,20 @@ pub fn join_user_handler(ctx: Context<JoinUser>, _session_id: String, team: u8)
 }
 
 #[derive(Accounts)]
-#[instruction(session_id: String)]
+#[instruction(session_index: u64)]
 pub struct JoinUser<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// CHECK: Game server authority
+    /// CHECK: Game server running the session, only compared to its authority
     pub game_server: AccountInfo<'info>,
 
     #[account(
         mut,
-        seeds = [b"game_session", session_id.as_bytes()],
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::InvalidSessionAuthority,
+        constraint = !game_session.frozen @ WagerError::SessionFrozen,
     )]
     pub game_session: Account<'info, GameSession>,
 
@@ -77,27 +107,45 @@ pub struct JoinUser<'info> {
     )]
     pub user_token_account: Account<'info, TokenAccount>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 4 + 4 + 4 + 8 + 8 + 8 + 8 + 8 + 4 + 1,
+        seeds = [b"player_profile", user.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// CHECK: Side bet of the user on this session, spectators who bet cannot join
+    #[account(
+        seeds = [b"side_bet", game_session.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = side_bet.data_is_empty() @ WagerError::BettorCannotJoin,
+    )]
+    pub side_bet: AccountInfo<'info>,
+
     /// CHECK: Vault PDA that holds the funds
     #[account(
         mut,
-        seeds = [b"vault", session_id.as_bytes()],
+        seeds = [b"vault", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.vault_bump,
     )]
     pub vault: AccountInfo<'info>,
 
     #[account(
         mut,
-        associated_token::mint = mint,
+        associated_token::mint = TOKEN_ID,
         associated_token::authority = vault,
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
-    #[account(
-        mut,
-        address = TOKEN_ID @ WagerError::InvalidMint
-    )]
-    pub mint: Account<'info, anchor_spl::token::Mint>,
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 }
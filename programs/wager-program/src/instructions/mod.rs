@@ -1,12 +1,62 @@
+pub mod add_session_delegate;
+pub mod admin_refund;
+pub mod advance_tournament;
+pub mod cancel_tournament;
+pub mod claim_side_bet;
+pub mod commit_match_log;
 pub mod create_game_session;
+pub mod create_lobby_template;
+pub mod create_session_from_template;
+pub mod create_tournament;
+pub mod create_tournament_match;
 pub mod distribute_winnings;
+pub mod forfeit_player;
+pub mod freeze_session;
+pub mod initialize_config;
+pub mod join_tournament;
 pub mod join_user;
 pub mod pay_to_spawn;
+pub mod place_side_bet;
 pub mod record_kill;
+pub mod record_kills_batch;
+pub mod record_series_round;
+pub mod refund_legacy_session;
 pub mod refund_wager;
+pub mod remove_session_delegate;
+pub mod set_paused;
+pub mod start_session;
+pub mod sweep_vault;
+pub mod transfer_session_authority;
+pub mod unfreeze_session;
+pub mod update_config;
+pub use add_session_delegate::*;
+pub use admin_refund::*;
+pub use advance_tournament::*;
+pub use cancel_tournament::*;
+pub use claim_side_bet::*;
+pub use commit_match_log::*;
 pub use create_game_session::*;
+pub use create_lobby_template::*;
+pub use create_session_from_template::*;
+pub use create_tournament::*;
+pub use create_tournament_match::*;
 pub use distribute_winnings::*;
+pub use forfeit_player::*;
+pub use freeze_session::*;
+pub use initialize_config::*;
+pub use join_tournament::*;
 pub use join_user::*;
 pub use pay_to_spawn::*;
+pub use place_side_bet::*;
 pub use record_kill::*;
+pub use record_kills_batch::*;
+pub use record_series_round::*;
+pub use refund_legacy_session::*;
 pub use refund_wager::*;
+pub use remove_session_delegate::*;
+pub use set_paused::*;
+pub use start_session::*;
+pub use sweep_vault::*;
+pub use transfer_session_authority::*;
+pub use unfreeze_session::*;
+pub use update_config::*;
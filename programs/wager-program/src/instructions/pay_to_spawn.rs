@@ -1,14 +1,13 @@
 use crate::{errors::WagerError, state::*, TOKEN_ID};
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount};
 
-pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, _session_id: String, team: u8) -> Result<()> {
+pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, _session_index: u64, team: u8) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
 
     // Check if game status is in progress and if it is a pay to spawn game
     require!(
-        game_session.status == GameStatus::InProgress && game_session.is_pay_to_spawn(),
+        game_session.is_playing() && game_session.is_pay_to_spawn(),
         WagerError::InvalidGameState
     );
 
@@ -18,7 +17,8 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, _session_id: String, team:
     // Check if team is full already
     let player_index = game_session.get_player_index(team, ctx.accounts.user.key())?;
 
-    let session_bet = game_session.session_bet;
+    // Fails once the player reached the purchase limit of the session
+    let price = game_session.spawn_purchase_price(team, player_index)?;
 
     // Transfer SPL tokens from user to vault using user's signature
     anchor_spl::token::transfer(
@@ -30,27 +30,30 @@ pub fn pay_to_spawn_handler(ctx: Context<PayToSpawn>, _session_id: String, team:
                 authority: ctx.accounts.user.to_account_info(),
             },
         ),
-        session_bet,
+        price,
     )?;
 
+    game_session.hold_deposit(price)?;
     game_session.add_spawns(team, player_index)?;
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(_session_id: String)]
+#[instruction(session_index: u64)]
 pub struct PayToSpawn<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    /// CHECK: Game server authority
+    /// CHECK: Game server running the session, only compared to its authority
     pub game_server: AccountInfo<'info>,
 
     #[account(
         mut,
-        seeds = [b"game_session", _session_id.as_bytes()],
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::InvalidSessionAuthority,
+        constraint = !game_session.frozen @ WagerError::SessionFrozen,
     )]
     pub game_session: Account<'info, GameSession>,
 
@@ -64,7 +67,7 @@ pub struct PayToSpawn<'info> {
     /// CHECK: Vault PDA that holds the funds
     #[account(
         mut,
-        seeds = [b"vault", _session_id.as_bytes()],
+        seeds = [b"vault", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.vault_bump,
     )]
     pub vault: AccountInfo<'info>,
@@ -77,6 +80,11 @@ pub struct PayToSpawn<'info> {
     pub vault_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 }
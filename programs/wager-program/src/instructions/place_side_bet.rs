@@ -0,0 +1,118 @@
+use crate::{errors::WagerError, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+pub fn place_side_bet_handler(
+    ctx: Context<PlaceSideBet>,
+    _session_index: u64,
+    team: u8,
+    amount: u64,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let bettor = ctx.accounts.bettor.key();
+
+    // Pay-to-spawn sessions have no winning team to bet on
+    require!(
+        game_session.status == GameStatus::WaitingForPlayers && !game_session.is_pay_to_spawn(),
+        WagerError::SideBetsClosed
+    );
+    require!(
+        !game_session.contains_player(bettor),
+        WagerError::PlayerCannotSideBet
+    );
+    require!(team == 0 || team == 1, WagerError::InvalidTeamSelection);
+
+    // A bettor may add to an earlier bet, but only on the same team
+    let side_bet = &mut ctx.accounts.side_bet;
+    if side_bet.bettor == Pubkey::default() {
+        side_bet.game_session = game_session.key();
+        side_bet.bettor = bettor;
+        side_bet.team = team;
+        side_bet.bump = ctx.bumps.side_bet;
+    }
+    require!(
+        amount > 0 && side_bet.team == team,
+        WagerError::InvalidSideBet
+    );
+
+    anchor_spl::token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.bettor_token_account.to_account_info(),
+                to: ctx.accounts.side_vault_token_account.to_account_info(),
+                authority: ctx.accounts.bettor.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    side_bet.amount = side_bet
+        .amount
+        .checked_add(amount)
+        .ok_or(WagerError::ArithmeticError)?;
+    game_session.side_pool.add_bet(team, amount)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_index: u64)]
+pub struct PlaceSideBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.bump,
+        constraint = !game_session.frozen @ WagerError::SessionFrozen,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + 32 + 32 + 1 + 8 + 1,
+        seeds = [b"side_bet", game_session.key().as_ref(), bettor.key().as_ref()],
+        bump
+    )]
+    pub side_bet: Account<'info, SideBet>,
+
+    #[account(
+        mut,
+        constraint = bettor_token_account.owner == bettor.key(),
+        constraint = bettor_token_account.mint == TOKEN_ID
+    )]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA owning the side bet tokens, kept apart from the player vault
+    #[account(
+        seeds = [b"side_vault", game_session.key().as_ref()],
+        bump
+    )]
+    pub side_vault: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        associated_token::mint = mint,
+        associated_token::authority = side_vault,
+    )]
+    pub side_vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(address = TOKEN_ID @ WagerError::InvalidMint)]
+    pub mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
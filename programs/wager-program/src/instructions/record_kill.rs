@@ -1,29 +1,45 @@
-use crate::{errors::WagerError, state::*};
+use crate::{errors::WagerError, events::KillsRecorded, state::*};
 use anchor_lang::prelude::*;
 
 pub fn record_kill_handler(
     ctx: Context<RecordKill>,
-    _session_id: String,
+    _session_index: u64,
     killer_team: u8,
     killer: Pubkey,
     victim_team: u8,
     victim: Pubkey,
 ) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
-    game_session.add_kill(killer_team, killer, victim_team, victim)?;
+    let kill = game_session.kill_event(killer_team, killer, victim_team, victim)?;
+    game_session.apply_kill_event(&kill)?;
+
+    emit!(KillsRecorded {
+        game_session: game_session.key(),
+        kills: vec![kill],
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(_session_id: String)]
+#[instruction(session_index: u64)]
 pub struct RecordKill<'info> {
     #[account(
         mut,
-        seeds = [b"game_session", _session_id.as_bytes()],
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.bump,
-        constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedKill,
+        constraint = !game_session.frozen @ WagerError::SessionFrozen,
+        constraint = game_session.is_operator(game_server.key()) @ WagerError::UnauthorizedKill,
     )]
     pub game_session: Account<'info, GameSession>,
 
     pub game_server: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 }
@@ -0,0 +1,29 @@
+use crate::{errors::WagerError, events::KillsRecorded, state::*};
+use anchor_lang::prelude::*;
+
+use super::RecordKill;
+
+pub fn record_kills_batch_handler(
+    ctx: Context<RecordKill>,
+    _session_index: u64,
+    kills: Vec<KillEvent>,
+) -> Result<()> {
+    require!(
+        !kills.is_empty() && kills.len() <= MAX_KILLS_PER_BATCH,
+        WagerError::InvalidKillBatch
+    );
+
+    // Any invalid kill fails the instruction, so the batch is applied all or nothing
+    let game_session = &mut ctx.accounts.game_session;
+    for kill in &kills {
+        game_session.apply_kill_event(kill)?;
+    }
+
+    emit!(KillsRecorded {
+        game_session: game_session.key(),
+        kills,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::{errors::WagerError, events::SeriesRoundRecorded};
+use anchor_lang::prelude::*;
+
+use super::RecordKill;
+
+pub fn record_series_round_handler(
+    ctx: Context<RecordKill>,
+    _session_index: u64,
+    winning_team: u8,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+
+    // Round winners decide the payout, so delegates cannot record them
+    require!(
+        game_session.authority == ctx.accounts.game_server.key(),
+        WagerError::UnauthorizedDistribution
+    );
+    game_session.record_series_round(winning_team)?;
+
+    // The series exists, recording the round would have failed otherwise
+    let series = game_session.series.unwrap_or_default();
+    emit!(SeriesRoundRecorded {
+        game_session: game_session.key(),
+        round: series.rounds_played - 1,
+        winning_team,
+        series_winner: series.winner(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
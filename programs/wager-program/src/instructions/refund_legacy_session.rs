@@ -0,0 +1,136 @@
+use crate::{errors::WagerError, payout::vault_residual, state::*, utils::is_paired, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{Token, TokenAccount};
+
+/// Migration path for sessions created before sessions were addressed by
+/// authority and index: unfinished sessions refund the bet of every joined
+/// player, then the legacy account is closed so its id can never be reused
+pub fn refund_legacy_session_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundLegacySession<'info>>,
+    session_id: String,
+) -> Result<()> {
+    let legacy_session =
+        LegacyGameSession::try_from_account(&ctx.accounts.legacy_game_session.to_account_info())?;
+    msg!("Migrating legacy session: {}", session_id);
+
+    require!(
+        legacy_session.authority == ctx.accounts.game_server.key(),
+        WagerError::UnauthorizedDistribution
+    );
+    require!(
+        ctx.accounts.vault.key()
+            == Pubkey::create_program_address(
+                &[
+                    b"vault",
+                    session_id.as_bytes(),
+                    &[legacy_session.vault_bump]
+                ],
+                &crate::ID
+            )
+            .map_err(|_| WagerError::InvalidLegacySession)?,
+        WagerError::InvalidLegacySession
+    );
+
+    // Completed sessions already paid out, they are only closed
+    if legacy_session.status != GameStatus::Completed {
+        // Make sure remaining accounts are in pairs
+        require!(
+            is_paired(ctx.remaining_accounts),
+            WagerError::InvalidRemainingAccounts
+        );
+
+        // Make sure the vault covers every refund before the first transfer
+        let joined_players = legacy_session.joined_players();
+        let residual = vault_residual(
+            ctx.accounts.vault_token_account.amount,
+            &vec![legacy_session.session_bet; joined_players.len()],
+        )?;
+
+        for player in joined_players {
+            // Find the player's account and token account in remaining_accounts
+            let player_index = ctx
+                .remaining_accounts
+                .iter()
+                .step_by(2) // Skip token accounts to only look at player accounts
+                .position(|acc| acc.key() == player)
+                .ok_or(WagerError::InvalidPlayer)?;
+
+            let player_token_account_info = &ctx.remaining_accounts[player_index * 2 + 1];
+            let player_token_account =
+                Account::<TokenAccount>::try_from(player_token_account_info)?;
+            require!(
+                player_token_account.owner == player,
+                WagerError::InvalidPlayerTokenAccount
+            );
+            require!(
+                player_token_account.mint == TOKEN_ID,
+                WagerError::InvalidTokenMint
+            );
+
+            anchor_spl::token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: ctx.accounts.vault_token_account.to_account_info(),
+                        to: player_token_account.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[&[
+                        b"vault",
+                        session_id.as_bytes(),
+                        &[legacy_session.vault_bump],
+                    ]],
+                ),
+                legacy_session.session_bet,
+            )?;
+        }
+
+        ctx.accounts.vault_token_account.reload()?;
+        require!(
+            ctx.accounts.vault_token_account.amount == residual,
+            WagerError::IncompleteDistribution
+        );
+    }
+
+    // Close the legacy account, its rent goes back to the game server
+    let legacy_account = ctx.accounts.legacy_game_session.to_account_info();
+    let game_server = ctx.accounts.game_server.to_account_info();
+    **game_server.try_borrow_mut_lamports()? = game_server
+        .lamports()
+        .checked_add(legacy_account.lamports())
+        .ok_or(WagerError::ArithmeticError)?;
+    **legacy_account.try_borrow_mut_lamports()? = 0;
+    legacy_account.assign(&system_program::ID);
+    legacy_account.realloc(0, false)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: String)]
+pub struct RefundLegacySession<'info> {
+    /// The game server authority that created the legacy session
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+
+    /// CHECK: Legacy game session, deserialized with its old layout by the handler
+    #[account(
+        mut,
+        seeds = [b"game_session", session_id.as_bytes()],
+        bump,
+    )]
+    pub legacy_game_session: UncheckedAccount<'info>,
+
+    /// CHECK: Legacy vault PDA, checked against the bump stored in the legacy session
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = TOKEN_ID,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
@@ -1,54 +1,102 @@
-use crate::{errors::WagerError, state::*, TOKEN_ID};
+use crate::{errors::WagerError, payout::vault_residual, state::*, utils::is_paired, TOKEN_ID};
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Token, TokenAccount};
 
 pub fn refund_wager_handler<'info>(
     ctx: Context<'_, '_, 'info, 'info, RefundWager<'info>>,
-    session_id: String,
+    session_index: u64,
 ) -> Result<()> {
     let game_session = &ctx.accounts.game_session;
-    msg!("Starting Refund for session: {}", session_id);
+    msg!("Starting Refund for session: {}", session_index);
 
-    let players = game_session.get_all_players();
-    msg!("Number of players: {}", players.len());
-    msg!(
-        "Number of remaining accounts: {}",
-        ctx.remaining_accounts.len()
-    );
+    // Only unsettled sessions are refunded, spawn purchases included
+    let refunds = game_session.refunds()?;
+
+    transfer_refunds(
+        ctx.remaining_accounts,
+        &refunds,
+        game_session,
+        &ctx.accounts.vault,
+        &mut ctx.accounts.vault_token_account,
+        &ctx.accounts.token_program,
+    )?;
+
+    // Mark session as completed and refund side bets
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.release_deposits(refunds.iter().map(|(_, amount)| amount).sum())?;
+    game_session.status = GameStatus::Completed;
+    game_session.side_pool.outcome = SidePoolOutcome::Cancelled;
+
+    Ok(())
+}
+
+/// Pays each (player, amount) refund out of the session vault. Players and
+/// their token accounts are passed as pairs in the remaining accounts.
+pub(crate) fn transfer_refunds<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    refunds: &[(Pubkey, u64)],
+    game_session: &GameSession,
+    vault: &AccountInfo<'info>,
+    vault_token_account: &mut Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    transfer_refunds_from(
+        remaining_accounts,
+        refunds,
+        vault,
+        &[
+            b"vault",
+            game_session.creator.as_ref(),
+            &game_session.session_index.to_le_bytes(),
+            &[game_session.vault_bump],
+        ],
+        vault_token_account,
+        token_program,
+    )
+}
+
+/// Pays each (player, amount) refund out of any program vault, signing with
+/// the seeds of the vault PDA
+pub(crate) fn transfer_refunds_from<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    refunds: &[(Pubkey, u64)],
+    vault: &AccountInfo<'info>,
+    vault_seeds: &[&[u8]],
+    vault_token_account: &mut Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    msg!("Number of players: {}", refunds.len());
+    msg!("Number of remaining accounts: {}", remaining_accounts.len());
 
     // We need at least one player and their token account
     require!(
-        !ctx.remaining_accounts.is_empty(),
+        !remaining_accounts.is_empty(),
         WagerError::InvalidRemainingAccounts
     );
 
     // Make sure remaining accounts are in pairs
     require!(
-        ctx.remaining_accounts.len() % 2 == 0,
+        is_paired(remaining_accounts),
         WagerError::InvalidRemainingAccounts
     );
 
-    for player in players {
-        // Skip default player
-        if player == Pubkey::default() {
-            continue;
-        }
+    // Make sure the vault covers every refund before the first transfer
+    let amounts: Vec<u64> = refunds.iter().map(|(_, refund)| *refund).collect();
+    let residual = vault_residual(vault_token_account.amount, &amounts)?;
 
-        let refund = game_session.session_bet;
+    for &(player, refund) in refunds {
         msg!("Earnings for player {}: {}", player, refund);
 
         // Find the player's account and token account in remaining_accounts
-        let player_index = ctx
-            .remaining_accounts
+        let player_index = remaining_accounts
             .iter()
             .step_by(2) // Skip token accounts to only look at player accounts
             .position(|acc| acc.key() == player)
             .ok_or(WagerError::InvalidPlayer)?;
 
         // Get player and token account from remaining accounts
-        let player_account = &ctx.remaining_accounts[player_index * 2];
-        let player_token_account_info = &ctx.remaining_accounts[player_index * 2 + 1];
+        let player_account = &remaining_accounts[player_index * 2];
+        let player_token_account_info = &remaining_accounts[player_index * 2 + 1];
         let player_token_account = Account::<TokenAccount>::try_from(player_token_account_info)?;
 
         // Verify player token account constraints
@@ -64,52 +112,54 @@ pub fn refund_wager_handler<'info>(
         );
 
         // Get vault balance before transfer
-        let vault_balance = ctx.accounts.vault_token_account.amount;
-        msg!("Vault balance before transfer: {}", vault_balance);
+        msg!(
+            "Vault balance before transfer: {}",
+            vault_token_account.amount
+        );
 
         // Transfer tokens from vault to player
         anchor_spl::token::transfer(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
+                token_program.to_account_info(),
                 anchor_spl::token::Transfer {
-                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    from: vault_token_account.to_account_info(),
                     to: player_token_account.to_account_info(),
-                    authority: ctx.accounts.vault.to_account_info(),
+                    authority: vault.to_account_info(),
                 },
-                &[&[
-                    b"vault",
-                    session_id.as_bytes(),
-                    &[ctx.accounts.game_session.vault_bump],
-                ]],
+                &[vault_seeds],
             ),
             refund,
         )?;
     }
 
-    // Mark session as completed
-    let game_session = &mut ctx.accounts.game_session;
-    game_session.status = GameStatus::Completed;
+    // Exactly the refunded amount left the vault
+    vault_token_account.reload()?;
+    require!(
+        vault_token_account.amount == residual,
+        WagerError::IncompleteDistribution
+    );
 
     Ok(())
 }
 #[derive(Accounts)]
-#[instruction(session_id: String)]
+#[instruction(session_index: u64)]
 pub struct RefundWager<'info> {
     /// The game server authority that created the session
     pub game_server: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"game_session", session_id.as_bytes()],
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.bump,
         constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+        constraint = !game_session.frozen @ WagerError::SessionFrozen,
     )]
     pub game_session: Account<'info, GameSession>,
 
     /// CHECK: Vault PDA that holds the funds
     #[account(
         mut,
-        seeds = [b"vault", session_id.as_bytes()],
+        seeds = [b"vault", game_session.creator.as_ref(), &session_index.to_le_bytes()],
         bump = game_session.vault_bump,
     )]
     pub vault: AccountInfo<'info>,
@@ -122,6 +172,4 @@ pub struct RefundWager<'info> {
     pub vault_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,28 @@
+use crate::{events::PauseChanged, state::*};
+use anchor_lang::prelude::*;
+
+pub fn set_paused_handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    ctx.accounts.global_config.set_paused(authority, paused)?;
+
+    emit!(PauseChanged {
+        authority,
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    /// Config admin, or the guardian when pausing
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
@@ -0,0 +1,83 @@
+use crate::{errors::WagerError, events::SessionStarted, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use super::refund_wager::transfer_refunds;
+
+pub fn start_session_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, StartSession<'info>>,
+    session_index: u64,
+    min_players_per_team: u8,
+    no_shows: Vec<Pubkey>,
+) -> Result<()> {
+    msg!("Starting session: {}", session_index);
+
+    // Full lobbies start on the last join, this only balances short ones
+    let game_session = &mut ctx.accounts.game_session;
+    let refunds = game_session.start_short(min_players_per_team, &no_shows)?;
+
+    // Players of the larger team beyond the smaller one get their bet back
+    if !refunds.is_empty() {
+        transfer_refunds(
+            ctx.remaining_accounts,
+            &refunds,
+            &ctx.accounts.game_session,
+            &ctx.accounts.vault,
+            &mut ctx.accounts.vault_token_account,
+            &ctx.accounts.token_program,
+        )?;
+        ctx.accounts
+            .game_session
+            .release_deposits(refunds.iter().map(|(_, amount)| amount).sum())?;
+    }
+
+    let game_session = &ctx.accounts.game_session;
+    emit!(SessionStarted {
+        game_session: game_session.key(),
+        team_size: game_session.team_a.player_count() as u8,
+        no_shows,
+        refunded: refunds.into_iter().map(|(player, _)| player).collect(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_index: u64)]
+pub struct StartSession<'info> {
+    pub game_server: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::InvalidSessionAuthority,
+        constraint = !game_session.frozen @ WagerError::SessionFrozen,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        mut,
+        seeds = [b"vault", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = TOKEN_ID,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        constraint = !global_config.paused @ WagerError::ProgramPaused,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
@@ -0,0 +1,113 @@
+use crate::{errors::WagerError, events::VaultSwept, state::*, TOKEN_ID};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+pub fn sweep_vault_handler(ctx: Context<SweepVault>, session_index: u64) -> Result<()> {
+    // Only fees, rounding and stray tokens are left once every deposit was settled
+    ctx.accounts.game_session.check_can_sweep()?;
+
+    let amount = ctx.accounts.vault_token_account.amount;
+    msg!(
+        "Sweeping {} from the vault of session {}",
+        amount,
+        session_index
+    );
+
+    let vault_seeds: &[&[u8]] = &[
+        b"vault",
+        ctx.accounts.game_session.creator.as_ref(),
+        &session_index.to_le_bytes(),
+        &[ctx.accounts.game_session.vault_bump],
+    ];
+
+    // Fees, rounding remainders and tokens sent to the vault by mistake
+    if amount > 0 {
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.destination_token_account.to_account_info(),
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[vault_seeds],
+            ),
+            amount,
+        )?;
+    }
+
+    // The empty vault token account is closed, its rent goes back to the game server
+    anchor_spl::token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::CloseAccount {
+            account: ctx.accounts.vault_token_account.to_account_info(),
+            destination: ctx.accounts.game_server.to_account_info(),
+            authority: ctx.accounts.vault.to_account_info(),
+        },
+        &[vault_seeds],
+    ))?;
+
+    // So is the vault PDA itself
+    let vault = ctx.accounts.vault.to_account_info();
+    let game_server = ctx.accounts.game_server.to_account_info();
+    **game_server.try_borrow_mut_lamports()? = game_server
+        .lamports()
+        .checked_add(vault.lamports())
+        .ok_or(WagerError::ArithmeticError)?;
+    **vault.try_borrow_mut_lamports()? = 0;
+
+    emit!(VaultSwept {
+        game_session: ctx.accounts.game_session.key(),
+        destination: ctx.accounts.destination_token_account.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_index: u64)]
+pub struct SweepVault<'info> {
+    /// The game server running the session
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+
+    #[account(
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::UnauthorizedDistribution,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Vault PDA that holds the funds
+    #[account(
+        mut,
+        seeds = [b"vault", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.vault_bump,
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = TOKEN_ID,
+        associated_token::authority = vault,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination_token_account.mint == TOKEN_ID @ WagerError::InvalidTokenMint,
+        constraint = global_config.is_sweep_destination(destination_token_account.key())
+            @ WagerError::InvalidSweepDestination,
+    )]
+    pub destination_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
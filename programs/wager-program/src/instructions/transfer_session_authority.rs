@@ -0,0 +1,36 @@
+use crate::{errors::WagerError, events::SessionAuthorityTransferred, state::*};
+use anchor_lang::prelude::*;
+
+pub fn transfer_session_authority_handler(
+    ctx: Context<SessionAuthority>,
+    _session_index: u64,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let previous_authority = game_session.authority;
+    game_session.transfer_authority(new_authority)?;
+
+    emit!(SessionAuthorityTransferred {
+        game_session: game_session.key(),
+        previous_authority,
+        new_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_index: u64)]
+pub struct SessionAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_session", game_session.creator.as_ref(), &session_index.to_le_bytes()],
+        bump = game_session.bump,
+        constraint = game_session.authority == game_server.key() @ WagerError::InvalidSessionAuthority,
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// The game server currently running the session
+    pub game_server: Signer<'info>,
+}
@@ -0,0 +1,7 @@
+use anchor_lang::prelude::*;
+
+use super::{freeze_session::set_session_frozen, FreezeSession};
+
+pub fn unfreeze_session_handler(ctx: Context<FreezeSession>, _session_index: u64) -> Result<()> {
+    set_session_frozen(ctx, false)
+}
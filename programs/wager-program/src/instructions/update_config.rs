@@ -0,0 +1,33 @@
+use crate::{errors::WagerError, state::*};
+use anchor_lang::prelude::*;
+
+pub fn update_config_handler(
+    ctx: Context<UpdateConfig>,
+    admin: Pubkey,
+    guardian: Pubkey,
+    bet_limits: BetLimits,
+    treasury: Option<Pubkey>,
+) -> Result<()> {
+    require!(admin != Pubkey::default(), WagerError::UnauthorizedConfig);
+    bet_limits.validate()?;
+
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.admin = admin;
+    global_config.guardian = guardian;
+    global_config.bet_limits = bet_limits;
+    global_config.treasury = treasury;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global_config"],
+        bump = global_config.bump,
+        has_one = admin @ WagerError::UnauthorizedConfig,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
@@ -1,7 +1,11 @@
 use anchor_lang::prelude::*;
 
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod match_log;
+pub mod payout;
+pub mod rating;
 pub mod state;
 pub mod utils;
 
@@ -15,52 +19,302 @@ pub const TOKEN_ID: Pubkey = pubkey!("BzeqmCjLZvMLSTrge9qZnyV8N2zNKBwAxQcZH2XEzF
 pub mod wager_program {
     use super::*;
 
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        guardian: Pubkey,
+        bet_limits: state::BetLimits,
+    ) -> Result<()> {
+        initialize_config_handler(ctx, guardian, bet_limits)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        admin: Pubkey,
+        guardian: Pubkey,
+        bet_limits: state::BetLimits,
+        treasury: Option<Pubkey>,
+    ) -> Result<()> {
+        update_config_handler(ctx, admin, guardian, bet_limits, treasury)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        set_paused_handler(ctx, paused)
+    }
+
+    pub fn freeze_session(ctx: Context<FreezeSession>, session_index: u64) -> Result<()> {
+        freeze_session_handler(ctx, session_index)
+    }
+
+    pub fn unfreeze_session(ctx: Context<FreezeSession>, session_index: u64) -> Result<()> {
+        unfreeze_session_handler(ctx, session_index)
+    }
+
+    pub fn admin_refund<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AdminRefund<'info>>,
+        session_index: u64,
+    ) -> Result<()> {
+        admin_refund_handler(ctx, session_index)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_game_session(
         ctx: Context<CreateGameSession>,
-        session_id: String,
+        session_id: Option<String>,
         bet_amount: u64,
         game_mode: state::GameMode,
+        rating_band: Option<state::RatingBand>,
+        spawn_config: Option<state::SpawnConfig>,
+        payout_config: Option<state::PayoutConfig>,
+        commit_match_log: bool,
+        best_of: Option<u8>,
     ) -> Result<()> {
-        create_game_session_handler(ctx, session_id, bet_amount, game_mode)
+        create_game_session_handler(
+            ctx,
+            session_id,
+            bet_amount,
+            game_mode,
+            rating_band,
+            spawn_config,
+            payout_config,
+            commit_match_log,
+            best_of,
+        )
     }
 
-    pub fn join_user(ctx: Context<JoinUser>, session_id: String, team: u8) -> Result<()> {
-        join_user_handler(ctx, session_id, team)
+    pub fn create_lobby_template(
+        ctx: Context<CreateLobbyTemplate>,
+        template_id: String,
+        game_mode: state::GameMode,
+        session_bet: u64,
+        fee_bps: u16,
+        join_timeout: i64,
+        bet_limits: Option<state::BetLimits>,
+    ) -> Result<()> {
+        create_lobby_template_handler(
+            ctx,
+            template_id,
+            game_mode,
+            session_bet,
+            fee_bps,
+            join_timeout,
+            bet_limits,
+        )
+    }
+
+    pub fn create_session_from_template(
+        ctx: Context<CreateSessionFromTemplate>,
+        template_id: String,
+        bet_amount: Option<u64>,
+    ) -> Result<()> {
+        create_session_from_template_handler(ctx, template_id, bet_amount)
+    }
+
+    pub fn join_user(ctx: Context<JoinUser>, session_index: u64, team: u8) -> Result<()> {
+        join_user_handler(ctx, session_index, team)
+    }
+
+    pub fn start_session<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StartSession<'info>>,
+        session_index: u64,
+        min_players_per_team: u8,
+        no_shows: Vec<Pubkey>,
+    ) -> Result<()> {
+        start_session_handler(ctx, session_index, min_players_per_team, no_shows)
+    }
+
+    pub fn forfeit_player(
+        ctx: Context<ForfeitPlayer>,
+        session_index: u64,
+        team: u8,
+        player: Pubkey,
+        end_match: bool,
+    ) -> Result<()> {
+        forfeit_player_handler(ctx, session_index, team, player, end_match)
     }
 
     pub fn distribute_winnings<'info>(
         ctx: Context<'_, '_, 'info, 'info, DistributeWinnings<'info>>,
-        session_id: String,
+        session_index: u64,
         winning_team: u8,
     ) -> Result<()> {
         //if winner takes all, distribute all winnings else distribute winnings for the winners
         if ctx.accounts.game_session.is_pay_to_spawn() {
-            distribute_pay_spawn_earnings(ctx, session_id)
+            distribute_pay_spawn_earnings(ctx, session_index)
         } else {
-            distribute_all_winnings_handler(ctx, session_id, winning_team)
+            distribute_all_winnings_handler(ctx, session_index, winning_team)
         }
     }
 
-    pub fn pay_to_spawn(ctx: Context<PayToSpawn>, session_id: String, team: u8) -> Result<()> {
-        pay_to_spawn_handler(ctx, session_id, team)
+    pub fn pay_to_spawn(ctx: Context<PayToSpawn>, session_index: u64, team: u8) -> Result<()> {
+        pay_to_spawn_handler(ctx, session_index, team)
     }
 
     pub fn record_kill(
         ctx: Context<RecordKill>,
-        session_id: String,
+        session_index: u64,
         killer_team: u8,
         killer: Pubkey,
         victim_team: u8,
         victim: Pubkey,
     ) -> Result<()> {
-        record_kill_handler(ctx, session_id, killer_team, killer, victim_team, victim)
+        record_kill_handler(ctx, session_index, killer_team, killer, victim_team, victim)
+    }
+
+    pub fn record_kills_batch(
+        ctx: Context<RecordKill>,
+        session_index: u64,
+        kills: Vec<state::KillEvent>,
+    ) -> Result<()> {
+        record_kills_batch_handler(ctx, session_index, kills)
+    }
+
+    pub fn record_series_round(
+        ctx: Context<RecordKill>,
+        session_index: u64,
+        winning_team: u8,
+    ) -> Result<()> {
+        record_series_round_handler(ctx, session_index, winning_team)
+    }
+
+    pub fn transfer_session_authority(
+        ctx: Context<SessionAuthority>,
+        session_index: u64,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        transfer_session_authority_handler(ctx, session_index, new_authority)
+    }
+
+    pub fn add_session_delegate(
+        ctx: Context<SessionAuthority>,
+        session_index: u64,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        add_session_delegate_handler(ctx, session_index, delegate)
+    }
+
+    pub fn remove_session_delegate(
+        ctx: Context<SessionAuthority>,
+        session_index: u64,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        remove_session_delegate_handler(ctx, session_index, delegate)
+    }
+
+    pub fn sweep_vault(ctx: Context<SweepVault>, session_index: u64) -> Result<()> {
+        sweep_vault_handler(ctx, session_index)
+    }
+
+    pub fn commit_match_log(
+        ctx: Context<RecordKill>,
+        session_index: u64,
+        root: [u8; 32],
+        event_count: u64,
+        is_final: bool,
+    ) -> Result<()> {
+        commit_match_log_handler(ctx, session_index, root, event_count, is_final)
+    }
+
+    pub fn place_side_bet(
+        ctx: Context<PlaceSideBet>,
+        session_index: u64,
+        team: u8,
+        amount: u64,
+    ) -> Result<()> {
+        place_side_bet_handler(ctx, session_index, team, amount)
+    }
+
+    pub fn claim_side_bet(ctx: Context<ClaimSideBet>, session_index: u64) -> Result<()> {
+        claim_side_bet_handler(ctx, session_index)
+    }
+
+    pub fn refund_legacy_session<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundLegacySession<'info>>,
+        session_id: String,
+    ) -> Result<()> {
+        refund_legacy_session_handler(ctx, session_id)
+    }
+
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        tournament_id: String,
+        entry_fee: u64,
+        bracket_size: u8,
+    ) -> Result<()> {
+        create_tournament_handler(ctx, tournament_id, entry_fee, bracket_size)
+    }
+
+    pub fn join_tournament(ctx: Context<JoinTournament>, tournament_id: String) -> Result<()> {
+        join_tournament_handler(ctx, tournament_id)
+    }
+
+    pub fn create_tournament_match(
+        ctx: Context<CreateTournamentMatch>,
+        tournament_id: String,
+        round: u8,
+        match_index: u8,
+    ) -> Result<()> {
+        create_tournament_match_handler(ctx, tournament_id, round, match_index)
+    }
+
+    pub fn cancel_tournament<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelTournament<'info>>,
+        tournament_id: String,
+    ) -> Result<()> {
+        cancel_tournament_handler(ctx, tournament_id)
+    }
+
+    pub fn advance_tournament(
+        ctx: Context<AdvanceTournament>,
+        tournament_id: String,
+        round: u8,
+        match_index: u8,
+    ) -> Result<()> {
+        advance_tournament_handler(ctx, tournament_id, round, match_index)
     }
 
     pub fn refund_wager<'info>(
         ctx: Context<'_, '_, 'info, 'info, RefundWager<'info>>,
-        session_id: String,
+        session_index: u64,
     ) -> Result<()> {
-        refund_wager_handler(ctx, session_id)
+        refund_wager_handler(ctx, session_index)
+    }
+}
+
+#[cfg(test)]
+mod test_utils {
+    use crate::state::*;
+    use anchor_lang::prelude::*;
+
+    /// 1v1 winner-takes-all session in progress with a bet of 1000 and empty
+    /// teams, tests override the fields they need with struct update syntax
+    pub fn test_session() -> GameSession {
+        GameSession {
+            session_id: String::new(),
+            session_index: 0,
+            creator: Pubkey::default(),
+            authority: Pubkey::new_unique(),
+            session_bet: 1000,
+            game_mode: GameMode::WinnerTakesAllOneVsOne,
+            team_a: Team::default(),
+            team_b: Team::default(),
+            status: GameStatus::InProgress,
+            created_at: 0,
+            bump: 0,
+            vault_bump: 0,
+            vault_token_bump: 0,
+            rating_band: None,
+            spawn_config: SpawnConfig::from_session_bet(1000),
+            payout_config: PayoutConfig::default(),
+            match_log: None,
+            side_pool: SideBetPool::default(),
+            series: None,
+            fee_bps: 0,
+            join_deadline: 0,
+            delegates: [Pubkey::default(); MAX_SESSION_DELEGATES],
+            frozen: false,
+            forfeit_winner: None,
+            held_deposits: 0,
+        }
     }
 }
 
@@ -68,109 +322,80 @@ pub mod wager_program {
 #[cfg(test)]
 mod security_tests {
     use super::*;
+    use crate::errors::WagerError;
+    use crate::payout::{split_pot, vault_residual};
     use crate::state::*;
+    use crate::test_utils::test_session;
 
     #[test]
     fn test_spawn_underflow_vulnerability() {
-        // PoC for Finding 1: Integer Underflow in Spawn Count
-        // This demonstrates that killing a player with 0 spawns causes underflow to u16::MAX (65535)
+        // Regression for Finding 1: Integer Underflow in Spawn Count
+        // Killing a player with 0 spawns used to underflow to u16::MAX (65535), it is now rejected
         
         // Setup: Create a mock GameSession with players
         let mut game_session = GameSession {
             session_id: "test_session".to_string(),
-            authority: Pubkey::new_unique(),
-            session_bet: 1000,
-            game_mode: GameMode::WinnerTakesAllOneVsOne,
             team_a: Team {
                 players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
                 total_bet: 1000,
                 player_spawns: [0, 0, 0, 0, 0], // Victim has 0 spawns
                 player_kills: [0, 0, 0, 0, 0],
+                ..Default::default()
             },
             team_b: Team {
                 players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
                 total_bet: 1000,
                 player_spawns: [10, 0, 0, 0, 0], // Killer has 10 spawns
                 player_kills: [0, 0, 0, 0, 0],
+                ..Default::default()
             },
-            status: GameStatus::InProgress,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            ..test_session()
         };
 
         // Get player keys for the test
         let victim_key = game_session.team_a.players[0]; // Team A, player 0
         let killer_key = game_session.team_b.players[0]; // Team B, player 0
 
-        // Verify preconditions
-        println!("Before attack:");
-        println!("Victim spawns: {}", game_session.team_a.player_spawns[0]);
-        println!("Killer spawns: {}", game_session.team_b.player_spawns[0]);
-        
         // Precondition: Victim has 0 spawns
         assert_eq!(game_session.team_a.player_spawns[0], 0, "Victim should have 0 spawns");
 
-        // Execute the attack: Call add_kill when victim has 0 spawns
+        // Attempt the attack: Call add_kill when victim has 0 spawns
         let result = game_session.add_kill(
             1, // killer_team (Team B)
             killer_key,
-            0, // victim_team (Team A) 
+            0, // victim_team (Team A)
             victim_key,
         );
 
-        // The function should succeed (no error checking for underflow)
-        assert!(result.is_ok(), "add_kill should succeed even with 0 spawns");
-
-        // Verify the exploit: Victim's spawn count should have underflowed to u16::MAX
-        println!("After attack:");
-        println!("Victim spawns: {}", game_session.team_a.player_spawns[0]);
-        println!("Killer kills: {}", game_session.team_b.player_kills[0]);
-
-        // Critical assertion: Spawn count underflowed from 0 to 65535
-        assert_eq!(
-            game_session.team_a.player_spawns[0], 
-            65535, // u16::MAX
-            "Victim spawn count should underflow to u16::MAX (65535)"
-        );
-
-        // Verify killer's kill count increased
-        assert_eq!(
-            game_session.team_b.player_kills[0],
-            1,
-            "Killer should have 1 kill"
-        );
+        // The kill is rejected instead of wrapping the spawn count around
+        assert_eq!(result.unwrap_err(), WagerError::PlayerHasNoSpawns.into());
 
-        println!("EXPLOIT CONFIRMED: Player with 0 spawns now has {} spawns due to underflow!", 
-                 game_session.team_a.player_spawns[0]);
+        // Nothing was recorded for either player
+        assert_eq!(game_session.team_a.player_spawns[0], 0, "Victim spawns must not underflow");
+        assert_eq!(game_session.team_a.player_deaths[0], 0, "Victim should have no deaths");
+        assert_eq!(game_session.team_b.player_kills[0], 0, "Killer should have no kills");
     }
 
     #[test]
-    fn test_multiple_underflows() {
-        // Test that multiple underflows continue to wrap around
+    fn test_repeated_kills_without_spawns_are_rejected() {
+        // Regression for Finding 1: every further kill of a player without spawns used to wrap around
         let mut game_session = GameSession {
             session_id: "test_session_2".to_string(),
-            authority: Pubkey::new_unique(),
-            session_bet: 1000,
-            game_mode: GameMode::WinnerTakesAllOneVsOne,
             team_a: Team {
                 players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
                 total_bet: 1000,
                 player_spawns: [1, 0, 0, 0, 0], // Victim starts with 1 spawn
                 player_kills: [0, 0, 0, 0, 0],
+                ..Default::default()
             },
             team_b: Team {
                 players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
                 total_bet: 1000,
                 player_spawns: [10, 0, 0, 0, 0],
                 player_kills: [0, 0, 0, 0, 0],
+                ..Default::default()
             },
-            status: GameStatus::InProgress,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            ..test_session()
         };
 
         let victim_key = game_session.team_a.players[0];
@@ -180,114 +405,109 @@ mod security_tests {
         game_session.add_kill(1, killer_key, 0, victim_key).unwrap();
         assert_eq!(game_session.team_a.player_spawns[0], 0);
 
-        // Kill 2: Underflow from 0 to 65535 spawns (vulnerability)
-        game_session.add_kill(1, killer_key, 0, victim_key).unwrap();
-        assert_eq!(game_session.team_a.player_spawns[0], 65535);
-
-        // Kill 3: Reduce from 65535 to 65534 spawns (continues wrapping)
-        game_session.add_kill(1, killer_key, 0, victim_key).unwrap();
-        assert_eq!(game_session.team_a.player_spawns[0], 65534);
+        // Kill 2 and 3: Rejected instead of wrapping around to 65535
+        for _ in 0..2 {
+            let result = game_session.add_kill(1, killer_key, 0, victim_key);
+            assert_eq!(result.unwrap_err(), WagerError::PlayerHasNoSpawns.into());
+            assert_eq!(game_session.team_a.player_spawns[0], 0);
+        }
 
-        println!("Multiple underflow test confirmed: {} spawns after 3 kills starting from 1", 
-                 game_session.team_a.player_spawns[0]);
+        // Only the legitimate kill was counted
+        assert_eq!(game_session.team_b.player_kills[0], 1);
+        assert_eq!(game_session.team_a.player_deaths[0], 1);
     }
 
     #[test]
-    fn test_pay_to_spawn_arithmetic_error() {
-        // PoC for Finding 4: Critical Arithmetic Error in Pay-to-Spawn Distribution
-        // This demonstrates the arbitrary division by 10 in earnings calculation
-        
-        // Setup: Create a pay-to-spawn game session with players who have kills/spawns
+    fn test_counter_and_pot_overflows() {
         let mut game_session = GameSession {
-            session_id: "pay_to_spawn_test".to_string(),
-            authority: Pubkey::new_unique(),
-            session_bet: 1000, // 1000 tokens per bet
-            game_mode: GameMode::PayToSpawnOneVsOne, // Pay-to-spawn mode
+            session_id: "test_session_3".to_string(),
+            session_bet: u64::MAX,
+            game_mode: GameMode::PayToSpawnFiveVsFive,
             team_a: Team {
                 players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
-                total_bet: 1000,
-                player_spawns: [5, 0, 0, 0, 0], // Player has 5 spawns (bought extra)
-                player_kills: [3, 0, 0, 0, 0],  // Player has 3 kills
+                player_spawns: [u16::MAX, 0, 0, 0, 0],
+                player_kills: [1, 0, 0, 0, 0],
+                ..Default::default()
             },
             team_b: Team {
                 players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
-                total_bet: 1000,
-                player_spawns: [2, 0, 0, 0, 0], // Player has 2 spawns
-                player_kills: [7, 0, 0, 0, 0],  // Player has 7 kills
+                player_spawns: [10, 0, 0, 0, 0],
+                player_kills: [u16::MAX, 0, 0, 0, 0],
+                ..Default::default()
             },
-            status: GameStatus::InProgress,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            spawn_config: SpawnConfig {
+                spawns_per_purchase: u16::MAX,
+                ..SpawnConfig::from_session_bet(1000)
+            },
+            ..test_session()
         };
-
         let player_a = game_session.team_a.players[0];
         let player_b = game_session.team_b.players[0];
 
-        println!("=== Pay-to-Spawn Earnings Calculation Analysis ===");
-        println!("Session bet amount: {}", game_session.session_bet);
+        // Kills plus spawns no longer wrap around in the pay-to-spawn score
+        assert_eq!(game_session.get_kills_and_spawns(player_a).unwrap_err(), WagerError::ArithmeticError.into());
 
-        // Test Player A earnings calculation
-        let player_a_kills_and_spawns = game_session.get_kills_and_spawns(player_a).unwrap();
-        let player_a_earnings = player_a_kills_and_spawns as u64 * game_session.session_bet / 10;
-        
-        println!("\nPlayer A:");
-        println!("  Kills: {}", game_session.team_a.player_kills[0]);
-        println!("  Spawns: {}", game_session.team_a.player_spawns[0]);
-        println!("  Total kills+spawns: {}", player_a_kills_and_spawns);
-        println!("  Calculated earnings: {} tokens", player_a_earnings);
-        println!("  Expected without /10: {} tokens", player_a_kills_and_spawns as u64 * game_session.session_bet);
-
-        // Test Player B earnings calculation  
-        let player_b_kills_and_spawns = game_session.get_kills_and_spawns(player_b).unwrap();
-        let player_b_earnings = player_b_kills_and_spawns as u64 * game_session.session_bet / 10;
-        
-        println!("\nPlayer B:");
-        println!("  Kills: {}", game_session.team_b.player_kills[0]);
-        println!("  Spawns: {}", game_session.team_b.player_spawns[0]);
-        println!("  Total kills+spawns: {}", player_b_kills_and_spawns);
-        println!("  Calculated earnings: {} tokens", player_b_earnings);
-        println!("  Expected without /10: {} tokens", player_b_kills_and_spawns as u64 * game_session.session_bet);
-
-        // Demonstrate the arithmetic error
-        // Player A: (3 kills + 5 spawns) * 1000 / 10 = 8 * 1000 / 10 = 800 tokens
-        assert_eq!(player_a_kills_and_spawns, 8);
-        assert_eq!(player_a_earnings, 800); // Arbitrary division by 10
-        
-        // Player B: (7 kills + 2 spawns) * 1000 / 10 = 9 * 1000 / 10 = 900 tokens  
-        assert_eq!(player_b_kills_and_spawns, 9);
-        assert_eq!(player_b_earnings, 900); // Arbitrary division by 10
-
-        // The issue: Why divide by 10? This seems arbitrary and reduces earnings significantly
-        let total_earnings_with_division = player_a_earnings + player_b_earnings; // 800 + 900 = 1700
-        let total_earnings_without_division = (player_a_kills_and_spawns as u64 * game_session.session_bet) + 
-                                           (player_b_kills_and_spawns as u64 * game_session.session_bet); // 8000 + 9000 = 17000
-        
-        println!("\n=== Economic Impact Analysis ===");
-        println!("Total earnings with /10 division: {} tokens", total_earnings_with_division);
-        println!("Total earnings without /10 division: {} tokens", total_earnings_without_division);
-        println!("Difference (lost to players): {} tokens", total_earnings_without_division - total_earnings_with_division);
-
-        // Critical assertions proving the vulnerability
-        assert_eq!(player_a_earnings, 800, "Player A earnings should be 800 due to /10 division");
-        assert_eq!(player_b_earnings, 900, "Player B earnings should be 900 due to /10 division");
-        
-        // Prove the division by 10 is arbitrary and significant
-        assert_eq!(total_earnings_with_division * 10, total_earnings_without_division, 
-                   "Division by 10 reduces total earnings by 90%");
+        // A killer at the kill limit cannot be credited another kill, and the victim keeps its spawn
+        let result = game_session.add_kill(1, player_b, 0, player_a);
+        assert_eq!(result.unwrap_err(), WagerError::ArithmeticError.into());
+        assert_eq!(game_session.team_a.player_spawns[0], u16::MAX);
+
+        // Spawn purchases cannot push the spawn count past u16::MAX
+        assert_eq!(game_session.add_spawns(0, 0).unwrap_err(), WagerError::ArithmeticError.into());
+        assert_eq!(game_session.team_a.spawn_purchases[0], 0);
 
-        println!("\nEXPLOIT CONFIRMED: Arbitrary division by 10 reduces player earnings by 90%!");
-        println!("Players receive {} instead of {} tokens due to hardcoded division", 
-                 total_earnings_with_division, total_earnings_without_division);
+        // Pots of huge bets are rejected instead of wrapping around
+        assert_eq!(game_session.total_pot().unwrap_err(), WagerError::ArithmeticError.into());
+
+        game_session.session_bet = 1000;
+        game_session.fee_bps = 500;
+        game_session.held_deposits = 2000;
+        assert_eq!(game_session.total_pot().unwrap(), 10_000);
+        assert_eq!(game_session.winner_payouts(1).unwrap(), vec![(player_b, 1900)]);
     }
 
     #[test]
-    fn test_vault_balance_validation_missing() {
-        // Test that demonstrates lack of vault balance validation
-        let mut game_session = GameSession {
+    fn test_pay_to_spawn_pot_follows_scores() {
+        // Earnings used to be (kills + spawns) * bet / 10, unrelated to the pot.
+        // The pot is now split by score and always adds up to the vault balance.
+        let game_session = GameSession {
+            game_mode: GameMode::PayToSpawnOneVsOne,
+            team_a: Team {
+                players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
+                total_bet: 1000,
+                player_spawns: [5, 0, 0, 0, 0], // Unused spawns earn nothing
+                player_kills: [3, 0, 0, 0, 0],
+                ..Default::default()
+            },
+            team_b: Team {
+                players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
+                total_bet: 1000,
+                player_spawns: [2, 0, 0, 0, 0],
+                player_kills: [7, 0, 0, 0, 0],
+                ..Default::default()
+            },
+            ..test_session()
+        };
+
+        let scores = game_session
+            .get_active_players()
+            .iter()
+            .map(|(team, slot, _)| game_session.get_player_score(*team, *slot).unwrap())
+            .collect::<Vec<u64>>();
+        assert_eq!(scores, vec![3 * DEFAULT_KILL_WEIGHT as u64, 7 * DEFAULT_KILL_WEIGHT as u64]);
+
+        let pot = 2000;
+        let (payouts, remainder) = split_pot(pot, &scores);
+        assert_eq!(payouts, vec![600, 1400]);
+        assert_eq!(remainder, 0);
+        assert_eq!(payouts.iter().sum::<u64>(), pot);
+    }
+
+    #[test]
+    fn test_payouts_are_checked_against_the_vault() {
+        // Regression test: pay-to-spawn payouts used to be sent without checking the vault balance
+        let game_session = GameSession {
             session_id: "vault_test".to_string(),
-            authority: Pubkey::new_unique(),
             session_bet: 10000, // High bet amount
             game_mode: GameMode::PayToSpawnFiveVsFive, // 5v5 mode for more players
             team_a: Team {
@@ -298,6 +518,7 @@ mod security_tests {
                 total_bet: 50000,
                 player_spawns: [10, 10, 10, 10, 10], // All players have many spawns
                 player_kills: [10, 10, 10, 10, 10],  // All players have many kills
+                ..Default::default()
             },
             team_b: Team {
                 players: [
@@ -307,253 +528,166 @@ mod security_tests {
                 total_bet: 50000,
                 player_spawns: [10, 10, 10, 10, 10],
                 player_kills: [10, 10, 10, 10, 10],
+                ..Default::default()
             },
-            status: GameStatus::InProgress,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            spawn_config: SpawnConfig::from_session_bet(10000),
+            ..test_session()
         };
 
-        println!("\n=== Vault Balance Validation Test ===");
-        
-        let mut total_calculated_earnings = 0u64;
-        let all_players = game_session.get_all_players();
-        
-        for player in all_players {
-            if player != Pubkey::default() {
-                let kills_and_spawns = game_session.get_kills_and_spawns(player).unwrap();
-                let earnings = kills_and_spawns as u64 * game_session.session_bet / 10;
-                total_calculated_earnings += earnings;
-                println!("Player {} earnings: {} tokens (kills+spawns: {})", 
-                         player, earnings, kills_and_spawns);
-            }
-        }
+        // Settlement now checks the payouts against the vault before transferring
+        let scores = game_session
+            .get_joined_players()
+            .iter()
+            .map(|(team, slot, _)| game_session.get_player_score(*team, *slot).unwrap())
+            .collect::<Vec<u64>>();
+        let pot = 100_000;
+        let (payouts, remainder) = split_pot(pot, &scores);
+        assert!(payouts.iter().all(|payout| *payout > 0), "Players should have earnings");
 
-        println!("Total calculated earnings for all players: {} tokens", total_calculated_earnings);
-        
-        // Prove that the function doesn't validate vault balance
-        // In a real scenario, this could lead to failed transactions
-        assert!(total_calculated_earnings > 0, "Players should have earnings");
-        println!("\nVULNERABILITY CONFIRMED: No vault balance validation before distribution!");
+        let mut outgoing = payouts.clone();
+        outgoing.push(remainder);
+        assert_eq!(vault_residual(pot, &outgoing).unwrap(), 0);
+
+        // A vault short of a single token is rejected before any transfer
+        assert_eq!(
+            vault_residual(pot - 1, &outgoing).unwrap_err(),
+            WagerError::InsufficientVaultBalance.into()
+        );
     }
 
     #[test]
     fn test_no_bet_amount_validation() {
-        // PoC for Finding 3: No Input Validation for Bet Amount
-        // This demonstrates that create_game_session_handler accepts any bet amount including zero
-        
-        // Test various bet amounts that should be rejected but are accepted
+        // Regression for Finding 3: No Input Validation for Bet Amount
+        // create_game_session_handler now checks every bet against the program limits
+        let limits = BetLimits {
+            min_bet: 100,
+            max_bet: 1_000_000,
+        };
+        assert!(limits.validate().is_ok());
+
         let test_cases = vec![
-            ("Zero bet", 0u64),
-            ("Extremely low bet", 1u64),
-            ("Normal bet", 1000u64),
-            ("Very high bet", u64::MAX),
+            ("Zero bet", 0u64, false),
+            ("Extremely low bet", 1u64, false),
+            ("Normal bet", 1000u64, true),
+            ("Very high bet", u64::MAX, false),
         ];
 
-        println!("=== Bet Amount Validation Test ===");
-
-        for (description, bet_amount) in test_cases {
-            println!("\nTesting {}: {} tokens", description, bet_amount);
-
-            // Simulate the core logic of create_game_session_handler
-            // The function directly assigns bet_amount without any validation
-            let session_bet = bet_amount; // This is what the function does: game_session.session_bet = bet_amount;
-
-            println!("  Result: Session created with bet amount: {}", session_bet);
-            
-            // Demonstrate the issues with each case
-            match bet_amount {
-                0 => {
-                    println!("  ISSUE: Zero-stake game created - no economic incentive");
-                    println!("  IMPACT: Players can join for free, breaking game economics");
-                    assert_eq!(session_bet, 0, "Zero bet should be rejected but is accepted");
-                }
-                1 => {
-                    println!("  ISSUE: Extremely low stakes may not cover transaction costs");
-                    println!("  IMPACT: Games may be economically unviable");
-                }
-                u64::MAX => {
-                    println!("  ISSUE: Unrealistically high bet amount accepted");
-                    println!("  IMPACT: Could cause overflow in calculations or unrealistic games");
-                    assert_eq!(session_bet, u64::MAX, "Max bet should be limited but isn't");
-                }
-                _ => {
-                    println!("  OK: Normal bet amount");
-                }
+        for (description, bet_amount, accepted) in test_cases {
+            let result = limits.check(bet_amount);
+            if accepted {
+                assert!(result.is_ok(), "{} should be accepted", description);
+            } else {
+                assert_eq!(result.unwrap_err(), WagerError::InvalidBetAmount.into(), "{}", description);
             }
         }
 
-        // Demonstrate spam potential with zero-bet games
-        println!("\n=== Spam Attack Simulation ===");
-        let spam_games = 100;
-        let mut total_zero_bet_games = 0;
-
-        for i in 0..spam_games {
-            let bet_amount = 0u64; // Attacker creates many zero-bet games
-            let _session_id = format!("spam_game_{}", i);
-            
-            // This would succeed in the actual function
-            total_zero_bet_games += 1;
-        }
+        // Even the widest limits never allow a free game
+        let widest = BetLimits {
+            min_bet: 1,
+            max_bet: u64::MAX,
+        };
+        assert_eq!(widest.check(0).unwrap_err(), WagerError::InvalidBetAmount.into());
 
-        println!("Created {} zero-bet games for spam", total_zero_bet_games);
-        println!("VULNERABILITY: Attacker can spam the system with free games");
-        
-        assert_eq!(total_zero_bet_games, spam_games, "All spam games should be created");
+        // Limits that would let zero-bet spam through cannot be configured
+        assert_eq!(BetLimits::exactly(0).validate().unwrap_err(), WagerError::InvalidBetLimits.into());
+        let inverted = BetLimits {
+            min_bet: 1000,
+            max_bet: 100,
+        };
+        assert_eq!(inverted.validate().unwrap_err(), WagerError::InvalidBetLimits.into());
     }
 
     #[test]
-    fn test_economic_impact_of_zero_bets() {
-        // Demonstrate how zero bets break the economic model
-        println!("\n=== Economic Impact Analysis ===");
-
-        // Scenario 1: Normal game with proper bets
-        let normal_bet = 1000u64;
-        let players_per_team = 2; // 1v1 game, but let's say 2 players total
-        let total_normal_pot = normal_bet * players_per_team;
-        
-        println!("Normal Game:");
-        println!("  Bet per player: {} tokens", normal_bet);
-        println!("  Total pot: {} tokens", total_normal_pot);
-        println!("  Economic incentive: HIGH");
-
-        // Scenario 2: Zero bet game
-        let zero_bet = 0u64;
-        let total_zero_pot = zero_bet * players_per_team;
-        
-        println!("\nZero-Bet Game:");
-        println!("  Bet per player: {} tokens", zero_bet);
-        println!("  Total pot: {} tokens", total_zero_pot);
-        println!("  Economic incentive: NONE");
-
-        // Demonstrate the problems
-        assert_eq!(total_zero_pot, 0, "Zero-bet games have no economic value");
-        
-        println!("\nProblems with zero-bet games:");
-        println!("1. No economic incentive for competitive play");
-        println!("2. Players can join unlimited games for free");
-        println!("3. System resources wasted on meaningless games");
-        println!("4. Potential for spam and abuse");
-        
-        // Show the difference in economic value
-        let economic_difference = total_normal_pot - total_zero_pot;
-        println!("\nEconomic value lost: {} tokens per zero-bet game", economic_difference);
-        
-        assert!(total_normal_pot > total_zero_pot, "Normal games should have higher economic value");
-        
-        println!("\nVULNERABILITY CONFIRMED: Zero-bet games break economic model!");
+    fn test_zero_pot_pays_nothing() {
+        // A zero-bet session has nothing to share, whatever the scores
+        let (payouts, remainder) = split_pot(0, &[10, 4]);
+        assert_eq!(payouts, vec![0, 0]);
+        assert_eq!(remainder, 0);
+
+        // Without any score the pot is shared evenly, the odd token is left over
+        let (payouts, remainder) = split_pot(2001, &[0, 0]);
+        assert_eq!(payouts, vec![1000, 1000]);
+        assert_eq!(remainder, 1);
     }
 
     #[test]
     fn test_overflow_risk_with_high_bets() {
-        // Test potential overflow scenarios with very high bet amounts
-        println!("\n=== Overflow Risk Analysis ===");
+        // Sessions whose pot or spawn purchases could overflow are rejected at creation
+        let mut game_session = GameSession {
+            session_id: "test_session".to_string(),
+            session_bet: u64::MAX,
+            game_mode: GameMode::WinnerTakesAllFiveVsFive,
+            status: GameStatus::WaitingForPlayers,
+            ..test_session()
+        };
 
-        let max_bet = u64::MAX;
-        let players_per_team = 5; // 5v5 game
-        let total_players = players_per_team * 2;
+        // 10 players betting u64::MAX overflow the pot
+        assert_eq!(game_session.validate_stakes().unwrap_err(), WagerError::InvalidBetAmount.into());
 
-        println!("High Bet Scenario:");
-        println!("  Bet per player: {} tokens", max_bet);
-        println!("  Total players: {}", total_players);
+        // The largest bet whose pot still fits is accepted
+        game_session.session_bet = u64::MAX / 10;
+        assert!(game_session.validate_stakes().is_ok());
 
-        // This calculation could overflow
-        let potential_overflow = max_bet.checked_mul(total_players as u64);
-        
-        match potential_overflow {
-            Some(total_pot) => {
-                println!("  Total pot: {} tokens", total_pot);
-                println!("  Status: No overflow");
-            }
-            None => {
-                println!("  Status: OVERFLOW DETECTED!");
-                println!("  RISK: Arithmetic overflow in pot calculations");
-                println!("  IMPACT: Could cause panics or incorrect calculations");
-            }
-        }
+        // Pay-to-spawn sessions also need room for every purchase of every player
+        game_session.game_mode = GameMode::PayToSpawnFiveVsFive;
+        assert_eq!(game_session.validate_stakes().unwrap_err(), WagerError::InvalidBetAmount.into());
+        game_session.session_bet = 1000;
+        assert!(game_session.validate_stakes().is_ok());
 
-        // Demonstrate that the function would accept this dangerous value
-        let accepted_bet = max_bet; // This is what create_game_session_handler does
-        assert_eq!(accepted_bet, u64::MAX, "Dangerous bet amount accepted without validation");
-        
-        println!("\nVULNERABILITY: Function accepts bet amounts that could cause overflow");
+        game_session.spawn_config.price_increment = u64::MAX / 2;
+        assert_eq!(game_session.validate_stakes().unwrap_err(), WagerError::InvalidBetAmount.into());
+
+        // Players must be able to buy every purchase without overflowing their spawns
+        game_session.spawn_config = SpawnConfig::from_session_bet(1000);
+        game_session.spawn_config.spawns_per_purchase = u16::MAX / 10;
+        assert_eq!(game_session.validate_stakes().unwrap_err(), WagerError::InvalidSpawnConfig.into());
     }
 
     #[test]
     fn test_duplicate_player_vulnerability() {
-        // PoC for Finding 6: No Duplicate Player Check
-        // This demonstrates that join_user_handler allows the same player to join multiple times
+        // Regression for Finding 6: No Duplicate Player Check
+        // join_user_handler now rejects a wallet that already holds a slot
         
         // Setup: Create a game session in WaitingForPlayers state
         let mut game_session = GameSession {
             session_id: "duplicate_test".to_string(),
-            authority: Pubkey::new_unique(),
-            session_bet: 1000,
             game_mode: GameMode::WinnerTakesAllThreeVsThree, // 3v3 for multiple slots
             team_a: Team {
                 players: [Pubkey::default(); 5], // All empty initially
                 total_bet: 0,
                 player_spawns: [10; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
             team_b: Team {
                 players: [Pubkey::default(); 5], // All empty initially
                 total_bet: 0,
                 player_spawns: [10; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
             status: GameStatus::WaitingForPlayers,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            ..test_session()
         };
 
         let duplicate_player = Pubkey::new_unique();
-        println!("=== Duplicate Player Check Test ===");
-        println!("Testing player: {}", duplicate_player);
 
-        // Simulate join_user_handler logic for Team A
-        println!("\n1. Joining player to Team A...");
-        let team_a_slot = game_session.get_player_empty_slot(0).unwrap(); // Team A
+        // Join the player to Team A the way join_user_handler does
+        game_session.check_can_join(duplicate_player).unwrap();
+        let team_a_slot = game_session.get_player_empty_slot(0).unwrap();
         game_session.team_a.players[team_a_slot] = duplicate_player;
-        game_session.team_a.player_spawns[team_a_slot] = 10;
-        game_session.team_a.player_kills[team_a_slot] = 0;
-        
-        println!("   ✓ Player joined Team A at slot {}", team_a_slot);
+        assert!(game_session.contains_player(duplicate_player));
 
-        // Simulate join_user_handler logic for Team B (same player!)
-        println!("\n2. Attempting to join SAME player to Team B...");
-        let team_b_slot_result = game_session.get_player_empty_slot(1); // Team B
-        
-        match team_b_slot_result {
-            Ok(team_b_slot) => {
-                // The vulnerability: No duplicate checking allows this
-                game_session.team_b.players[team_b_slot] = duplicate_player;
-                game_session.team_b.player_spawns[team_b_slot] = 10;
-                game_session.team_b.player_kills[team_b_slot] = 0;
-                
-                println!("   ✓ VULNERABILITY: Same player joined Team B at slot {}", team_b_slot);
-                
-                // Verify the duplicate exists
-                assert_eq!(game_session.team_a.players[team_a_slot], duplicate_player);
-                assert_eq!(game_session.team_b.players[team_b_slot], duplicate_player);
-                
-                println!("\n   EXPLOIT CONFIRMED: Player {} is on BOTH teams!", duplicate_player);
-            }
-            Err(_) => {
-                println!("   Team B full, but duplicate check still missing");
-            }
-        }
+        // Joining the SAME player to Team B is rejected although Team B has room
+        assert!(game_session.get_player_empty_slot(1).is_ok());
+        assert_eq!(
+            game_session.check_can_join(duplicate_player).unwrap_err(),
+            WagerError::PlayerAlreadyJoined.into()
+        );
 
-        // Demonstrate the unfair advantage
-        println!("\n=== Unfair Gameplay Analysis ===");
-        println!("Player {} advantages:", duplicate_player);
-        println!("1. Can play on both teams simultaneously");
-        println!("2. Has inside information about both team strategies");
-        println!("3. Can manipulate game outcome by controlling both sides");
-        println!("4. Gets double the spawns and can earn from both teams");
+        // Other wallets can still join
+        assert!(game_session.check_can_join(Pubkey::new_unique()).is_ok());
+        assert!(!game_session.contains_player(Pubkey::default()));
     }
 
     #[test]
@@ -561,59 +695,66 @@ mod security_tests {
         // Test joining the same player to multiple slots on the same team
         let mut game_session = GameSession {
             session_id: "same_team_duplicate".to_string(),
-            authority: Pubkey::new_unique(),
-            session_bet: 1000,
             game_mode: GameMode::WinnerTakesAllFiveVsFive, // 5v5 for more slots
             team_a: Team {
                 players: [Pubkey::default(); 5],
                 total_bet: 0,
                 player_spawns: [10; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
             team_b: Team {
                 players: [Pubkey::default(); 5],
                 total_bet: 0,
                 player_spawns: [10; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
             status: GameStatus::WaitingForPlayers,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            ..test_session()
         };
 
         let duplicate_player = Pubkey::new_unique();
-        println!("\n=== Same Team Multiple Slots Test ===");
-        println!("Testing player: {}", duplicate_player);
 
         // Join player to first slot
+        game_session.check_can_join(duplicate_player).unwrap();
         let slot1 = game_session.get_player_empty_slot(0).unwrap();
         game_session.team_a.players[slot1] = duplicate_player;
-        println!("Player joined Team A slot {}", slot1);
 
-        // Simulate scenario where another slot is available and same player joins again
+        // A second slot on the same team is rejected too
+        assert_eq!(
+            game_session.check_can_join(duplicate_player).unwrap_err(),
+            WagerError::PlayerAlreadyJoined.into()
+        );
+        assert_eq!(game_session.get_player_index(0, duplicate_player).unwrap(), slot1);
+        assert_eq!(game_session.get_kills_and_spawns(duplicate_player).unwrap(), 10);
+
+        // Should a wallet end up in several slots anyway, lookups refuse to pick one
         let slot2 = game_session.get_player_empty_slot(0).unwrap();
         game_session.team_a.players[slot2] = duplicate_player;
-        println!("Same player joined Team A slot {}", slot2);
+        assert_eq!(
+            game_session.get_player_index(0, duplicate_player).unwrap_err(),
+            WagerError::AmbiguousPlayer.into()
+        );
+        assert_eq!(
+            game_session.get_kills_and_spawns(duplicate_player).unwrap_err(),
+            WagerError::AmbiguousPlayer.into()
+        );
 
-        // Verify duplicate exists on same team
-        assert_eq!(game_session.team_a.players[slot1], duplicate_player);
-        assert_eq!(game_session.team_a.players[slot2], duplicate_player);
+        // The same holds across teams
+        game_session.team_a.players[slot2] = Pubkey::default();
+        game_session.team_b.players[0] = duplicate_player;
+        assert_eq!(game_session.get_player_index(0, duplicate_player).unwrap(), slot1);
+        assert_eq!(
+            game_session.get_kills_and_spawns(duplicate_player).unwrap_err(),
+            WagerError::AmbiguousPlayer.into()
+        );
 
-        println!("\nVULNERABILITY: Player {} occupies multiple slots on Team A", duplicate_player);
-        println!("Slots occupied: {} and {}", slot1, slot2);
-        
-        // Count total slots occupied by this player
-        let occupied_slots = game_session.team_a.players
-            .iter()
-            .filter(|&&p| p == duplicate_player)
-            .count();
-        
-        println!("Total slots occupied by one player: {}", occupied_slots);
-        assert!(occupied_slots > 1, "Player should occupy multiple slots");
-        
-        println!("IMPACT: Single player controls multiple game positions");
+        // Empty slots never resolve to a player
+        assert_eq!(
+            game_session.get_player_index(1, Pubkey::default()).unwrap_err(),
+            WagerError::PlayerNotFound.into()
+        );
     }
 
     #[test]
@@ -621,107 +762,69 @@ mod security_tests {
         // Show what proper duplicate checking should look like
         println!("\n=== Recommended Duplicate Prevention Logic ===");
 
-        let mut game_session = GameSession {
+        let game_session = GameSession {
             session_id: "proper_validation".to_string(),
-            authority: Pubkey::new_unique(),
-            session_bet: 1000,
-            game_mode: GameMode::WinnerTakesAllOneVsOne,
             team_a: Team {
                 players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
                 total_bet: 1000,
                 player_spawns: [10; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
             team_b: Team {
                 players: [Pubkey::default(); 5],
                 total_bet: 0,
                 player_spawns: [10; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
             status: GameStatus::WaitingForPlayers,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            ..test_session()
         };
 
         let test_player = game_session.team_a.players[0]; // Already in team A
-        println!("Testing duplicate prevention for player: {}", test_player);
-
-        // Proper validation logic that should exist
-        fn check_player_already_in_game(game_session: &GameSession, player: Pubkey) -> bool {
-            // Check team A
-            for &p in &game_session.team_a.players {
-                if p == player && p != Pubkey::default() {
-                    return true;
-                }
-            }
-            // Check team B  
-            for &p in &game_session.team_b.players {
-                if p == player && p != Pubkey::default() {
-                    return true;
-                }
-            }
-            false
-        }
 
-        let is_duplicate = check_player_already_in_game(&game_session, test_player);
-        println!("Duplicate check result: {}", is_duplicate);
+        // join_user_handler rejects the player through contains_player
+        assert!(game_session.contains_player(test_player));
+        assert!(game_session.check_can_join(test_player).is_err());
 
-        if is_duplicate {
-            println!("✓ PROPER BEHAVIOR: Player already in game, join should be rejected");
-        } else {
-            println!("✓ PROPER BEHAVIOR: Player not in game, join can proceed");
-        }
-
-        assert!(is_duplicate, "Player should be detected as already in game");
-        
-        println!("\nCURRENT VULNERABILITY: No such duplicate checking exists in join_user_handler");
-        println!("RECOMMENDATION: Add duplicate player validation before allowing joins");
+        let new_player = Pubkey::new_unique();
+        assert!(!game_session.contains_player(new_player));
+        assert!(game_session.check_can_join(new_player).is_ok());
     }
 
     #[test]
-    fn test_unlimited_spawn_purchases() {
-        // PoC for Finding 7: No Spawn Purchase Limits
-        // This demonstrates that pay_to_spawn_handler allows unlimited spawn purchases
-        
+    fn test_spawn_purchases_stop_at_the_limit() {
+        // Regression test for Finding 7: spawn purchases used to be unlimited
         // Setup: Create a pay-to-spawn game session with a player
         let mut game_session = GameSession {
-            session_id: "unlimited_spawns_test".to_string(),
-            authority: Pubkey::new_unique(),
-            session_bet: 1000, // 1000 tokens per spawn purchase
+            session_id: "spawn_limit_test".to_string(),
             game_mode: GameMode::PayToSpawnOneVsOne, // Pay-to-spawn mode
             team_a: Team {
                 players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
                 total_bet: 1000,
                 player_spawns: [10, 0, 0, 0, 0], // Player starts with 10 spawns
                 player_kills: [5, 0, 0, 0, 0],   // Player has some kills
+                ..Default::default()
             },
             team_b: Team {
                 players: [Pubkey::default(); 5],
                 total_bet: 0,
                 player_spawns: [0; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
-            status: GameStatus::InProgress, // Game must be in progress for pay-to-spawn
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            ..test_session()
         };
 
         let player = game_session.team_a.players[0];
         let player_index = 0;
         let team = 0; // Team A
 
-        println!("=== Unlimited Spawn Purchase Test ===");
-        println!("Player: {}", player);
-        println!("Session bet per spawn purchase: {} tokens", game_session.session_bet);
-        println!("Initial spawns: {}", game_session.team_a.player_spawns[player_index]);
-
         // Simulate multiple spawn purchases (pay_to_spawn_handler logic)
         let mut total_cost = 0u64;
         let purchase_attempts = 50; // Try to buy 50 times
+        let max_purchases = game_session.spawn_config.max_purchases;
 
         for purchase_round in 1..=purchase_attempts {
             // Simulate the core logic of pay_to_spawn_handler
@@ -733,45 +836,37 @@ mod security_tests {
             let found_player_index = game_session.get_player_index(team, player).unwrap();
             assert_eq!(found_player_index, player_index);
 
-            // 3. Transfer cost (simulated - in real scenario this would be tokens)
-            total_cost += game_session.session_bet;
+            // 3. Price the purchase, rejected once the limit is reached
+            let price = game_session.spawn_purchase_price(team, player_index);
+            if purchase_round > max_purchases {
+                assert!(price.is_err(), "Purchase {} should exceed the limit", purchase_round);
+                assert!(game_session.add_spawns(team, player_index).is_err());
+                continue;
+            }
+            total_cost += price.unwrap();
 
-            // 4. Add spawns (this is the vulnerable part - no limits!)
+            // 4. Add spawns
             game_session.add_spawns(team, player_index).unwrap();
 
             let current_spawns = game_session.team_a.player_spawns[player_index];
-            
-            if purchase_round <= 10 || purchase_round % 10 == 0 {
-                println!("Purchase {}: {} spawns (cost: {} tokens)", 
-                         purchase_round, current_spawns, total_cost);
-            }
-
-            // Verify spawns keep increasing without limit
             let expected_spawns = 10 + (purchase_round * 10); // Initial 10 + 10 per purchase
             assert_eq!(current_spawns, expected_spawns, 
                        "Spawns should increase by 10 each purchase");
         }
 
         let final_spawns = game_session.team_a.player_spawns[player_index];
-        println!("\n=== Purchase Results ===");
-        println!("Total purchases: {}", purchase_attempts);
-        println!("Final spawn count: {}", final_spawns);
-        println!("Total cost paid: {} tokens", total_cost);
 
-        // Demonstrate the problem
-        assert_eq!(final_spawns, 10 + (purchase_attempts * 10)); // 510 spawns total
-        assert_eq!(total_cost, purchase_attempts as u64 * game_session.session_bet); // 50,000 tokens
-
-        println!("\nVULNERABILITY CONFIRMED: Player purchased {} spawns without any limits!", final_spawns);
-        println!("IMPACT: Game could become never-ending with unlimited spawn purchases");
+        // Purchases stop at the session limit
+        assert_eq!(final_spawns, 10 + (max_purchases * 10)); // 110 spawns total
+        assert_eq!(total_cost, max_purchases as u64 * game_session.session_bet); // 10,000 tokens
+        assert_eq!(game_session.team_a.spawn_purchases[player_index], max_purchases);
     }
 
     #[test]
-    fn test_economic_imbalance_from_unlimited_spawns() {
-        // Demonstrate economic imbalance when players can buy unlimited spawns
+    fn test_spawn_advantage_is_bounded_by_the_limit() {
+        // Regression test for Finding 7: a rich player could outbuy everyone else
         let mut game_session = GameSession {
-            session_id: "economic_imbalance_test".to_string(),
-            authority: Pubkey::new_unique(),
+            session_id: "spawn_advantage_test".to_string(),
             session_bet: 500, // Lower cost for demonstration
             game_mode: GameMode::PayToSpawnThreeVsThree,
             team_a: Team {
@@ -779,174 +874,103 @@ mod security_tests {
                 total_bet: 1500,
                 player_spawns: [10, 10, 10, 0, 0], // All players start with 10 spawns
                 player_kills: [2, 3, 1, 0, 0],
+                ..Default::default()
             },
             team_b: Team {
                 players: [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default()],
                 total_bet: 1500,
                 player_spawns: [10, 10, 10, 0, 0],
                 player_kills: [1, 2, 4, 0, 0],
+                ..Default::default()
             },
-            status: GameStatus::InProgress,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            spawn_config: SpawnConfig::from_session_bet(500),
+            ..test_session()
         };
 
-        println!("\n=== Economic Imbalance Analysis ===");
-        println!("Spawn purchase cost: {} tokens", game_session.session_bet);
-
-        // Scenario 1: Normal gameplay (limited spawns)
-        let normal_total_spawns: u16 = game_session.team_a.player_spawns.iter().sum::<u16>() + 
-                                      game_session.team_b.player_spawns.iter().sum::<u16>();
-        println!("\nNormal Gameplay:");
-        println!("  Total spawns in game: {}", normal_total_spawns);
-
-        // Scenario 2: Rich player buys unlimited spawns
+        // Rich player tries to buy far more spawns than the limit
         let rich_player_team = 0;
         let rich_player_index = 0;
-        let spawn_purchases = 100; // Rich player buys 100 times
-
-        println!("\nRich Player Scenario:");
-        println!("  Player buys {} additional spawn packages", spawn_purchases);
+        let spawn_purchases = 100;
 
         let mut rich_player_cost = 0u64;
+        let mut rejected_purchases = 0;
         for _ in 0..spawn_purchases {
-            rich_player_cost += game_session.session_bet;
-            game_session.add_spawns(rich_player_team, rich_player_index).unwrap();
+            match game_session.spawn_purchase_price(rich_player_team, rich_player_index) {
+                Ok(price) => {
+                    rich_player_cost += price;
+                    game_session.add_spawns(rich_player_team, rich_player_index).unwrap();
+                }
+                Err(_) => rejected_purchases += 1,
+            }
         }
 
         let rich_player_spawns = game_session.team_a.player_spawns[rich_player_index];
-        println!("  Rich player spawns: {}", rich_player_spawns);
-        println!("  Rich player cost: {} tokens", rich_player_cost);
 
-        // Demonstrate the imbalance
-        let spawn_advantage = rich_player_spawns as f64 / 10.0; // Compared to normal 10 spawns
-        println!("\n=== Economic Imbalance Impact ===");
-        println!("Rich player advantage: {:.1}x more spawns than normal players", spawn_advantage);
-        println!("Game balance: COMPLETELY BROKEN");
-
-        assert!(rich_player_spawns > 1000, "Rich player should have excessive spawns");
-        assert!(spawn_advantage > 100.0, "Spawn advantage should be extreme");
-        
-        println!("\nVULNERABILITY: Economic imbalance allows pay-to-win scenarios");
+        // The purchase limit bounds the advantage and what the player can spend
+        assert_eq!(rejected_purchases, spawn_purchases - DEFAULT_MAX_SPAWN_PURCHASES);
+        assert_eq!(rich_player_spawns, 10 + DEFAULT_MAX_SPAWN_PURCHASES * DEFAULT_SPAWNS_PER_PURCHASE);
+        assert_eq!(
+            rich_player_cost,
+            game_session.spawn_config.total_price(DEFAULT_MAX_SPAWN_PURCHASES).unwrap()
+        );
     }
 
     #[test]
-    fn test_recommended_spawn_limits() {
-        // Show what proper spawn purchase limits should look like
-        println!("\n=== Recommended Spawn Purchase Limits ===");
-
-        let max_spawns_per_player = 50u16; // Reasonable limit
-        let max_purchases_per_game = 10u8; // Purchase limit
-
-        println!("Recommended limits:");
-        println!("  Maximum spawns per player: {}", max_spawns_per_player);
-        println!("  Maximum purchases per game: {}", max_purchases_per_game);
-
-        // Simulate proper validation logic
-        fn validate_spawn_purchase(
-            current_spawns: u16,
-            purchases_made: u8,
-            max_spawns: u16,
-            max_purchases: u8,
-        ) -> std::result::Result<(), &'static str> {
-            if current_spawns >= max_spawns {
-                return Err("Maximum spawns reached");
-            }
-            if purchases_made >= max_purchases {
-                return Err("Maximum purchases per game reached");
-            }
-            Ok(())
-        }
-
-        // Test the validation logic
-        let test_cases = vec![
-            (10, 0, "Should succeed - normal case"),
-            (50, 5, "Should fail - max spawns reached"),
-            (30, 10, "Should fail - max purchases reached"),
-            (45, 9, "Should succeed - within all limits"),
-        ];
+    fn test_spawn_purchase_limit_is_enforced() {
+        let mut game_session = GameSession {
+            game_mode: GameMode::PayToSpawnOneVsOne,
+            spawn_config: SpawnConfig {
+                max_purchases: 2,
+                ..SpawnConfig::from_session_bet(1000)
+            },
+            ..test_session()
+        };
+        game_session.team_a.players[0] = Pubkey::new_unique();
+        game_session.team_a.player_spawns[0] = INITIAL_SPAWNS;
 
-        for (spawns, purchases, description) in test_cases {
-            let result = validate_spawn_purchase(spawns, purchases, max_spawns_per_player, max_purchases_per_game);
-            
-            match result {
-                Ok(()) => println!("✓ {}: Purchase allowed", description),
-                Err(reason) => println!("✗ {}: Purchase denied - {}", description, reason),
-            }
+        for _ in 0..2 {
+            assert_eq!(game_session.spawn_purchase_price(0, 0).unwrap(), 1000);
+            game_session.add_spawns(0, 0).unwrap();
         }
-
-        println!("\nCURRENT VULNERABILITY: No such limits exist in pay_to_spawn_handler");
-        println!("IMPACT: Players can purchase unlimited spawns without restrictions");
+        assert_eq!(
+            game_session.spawn_purchase_price(0, 0).unwrap_err(),
+            WagerError::SpawnPurchaseLimitReached.into()
+        );
+        assert_eq!(
+            game_session.add_spawns(0, 0).unwrap_err(),
+            WagerError::SpawnPurchaseLimitReached.into()
+        );
+        assert_eq!(game_session.team_a.spawn_purchases[0], 2);
+        assert_eq!(
+            game_session.team_a.player_spawns[0],
+            INITIAL_SPAWNS + 2 * DEFAULT_SPAWNS_PER_PURCHASE
+        );
     }
 
     #[test]
     fn test_refund_completed_games() {
-        // PoC for Finding 8: No Game State Validation in Refunds
-        // This demonstrates that refund_wager_handler allows refunds in any game state
-        
-        // Demonstrate refunding games that are already completed
+        // Completed sessions were refunded again on top of their payouts
         let mut game_session = GameSession {
-            session_id: "completed_game_refund".to_string(),
-            authority: Pubkey::new_unique(),
-            session_bet: 1000,
-            game_mode: GameMode::WinnerTakesAllOneVsOne,
-            team_a: Team {
-                players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
-                total_bet: 1000,
-                player_spawns: [0, 0, 0, 0, 0], // Game finished - no spawns left
-                player_kills: [5, 0, 0, 0, 0],
-            },
-            team_b: Team {
-                players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
-                total_bet: 1000,
-                player_spawns: [3, 0, 0, 0, 0], // Winner still has spawns
-                player_kills: [2, 0, 0, 0, 0],
-            },
-            status: GameStatus::Completed, // Game is already completed!
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            status: GameStatus::Completed,
+            ..test_session()
         };
+        game_session.team_a.players[0] = Pubkey::new_unique();
+        game_session.team_b.players[0] = Pubkey::new_unique();
 
-        println!("=== Refund Completed Game Test ===");
-        println!("Game should NOT be refundable - it's already completed");
-
-        // Simulate the core logic of refund_wager_handler
-        let players = game_session.get_all_players();
-        println!("Players to refund: {}", players.len());
-
-        // The vulnerability: No state validation before refunding
-        let mut total_refund_amount = 0u64;
-        for player in players {
-            if player != Pubkey::default() {
-                let refund = game_session.session_bet;
-                total_refund_amount += refund;
-                println!("Refunding {} tokens to player {}", refund, player);
-            }
-        }
-
-        // The function would mark the game as completed (again!)
-        game_session.status = GameStatus::Completed;
-
-        println!("\n=== Refund Results ===");
-        println!("Total refund amount: {} tokens", total_refund_amount);
+        assert_eq!(
+            game_session.refunds().unwrap_err(),
+            WagerError::InvalidGameState.into()
+        );
 
-        // This should NOT happen - completed games shouldn't be refundable
-        assert_eq!(total_refund_amount, 2000); // 2 players * 1000 tokens each
-        
-        println!("\nVULNERABILITY CONFIRMED: Completed game was refunded!");
-        println!("IMPACT: Players could get refunds for games they already won/lost");
+        game_session.status = GameStatus::InProgress;
+        assert_eq!(game_session.refunds().unwrap().len(), 2);
     }
 
     #[test]
-    fn test_vault_balance_validation_missing_in_refunds() {
-        // Demonstrate lack of vault balance validation before refunds
-        let mut game_session = GameSession {
+    fn test_refunds_are_checked_against_the_vault() {
+        // Regression test: refunds used to be sent without checking the vault balance
+        let game_session = GameSession {
             session_id: "vault_balance_test".to_string(),
-            authority: Pubkey::new_unique(),
             session_bet: 10000, // High bet amount
             game_mode: GameMode::WinnerTakesAllFiveVsFive,
             team_a: Team {
@@ -957,6 +981,7 @@ mod security_tests {
                 total_bet: 50000,
                 player_spawns: [10; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
             team_b: Team {
                 players: [
@@ -966,103 +991,1693 @@ mod security_tests {
                 total_bet: 50000,
                 player_spawns: [10; 5],
                 player_kills: [0; 5],
+                ..Default::default()
             },
-            status: GameStatus::InProgress,
-            created_at: 0,
-            bump: 0,
-            vault_bump: 0,
-            vault_token_bump: 0,
+            spawn_config: SpawnConfig::from_session_bet(10000),
+            ..test_session()
         };
 
-        println!("\n=== Vault Balance Validation Test ===");
-        println!("Session bet: {} tokens per player", game_session.session_bet);
-
-        let players = game_session.get_all_players();
-        let active_players: Vec<_> = players.into_iter().filter(|&p| p != Pubkey::default()).collect();
-        
-        println!("Active players: {}", active_players.len());
-
-        // Calculate total refund needed
-        let total_refund_needed = active_players.len() as u64 * game_session.session_bet;
-        println!("Total refund needed: {} tokens", total_refund_needed);
+        let refunds: Vec<u64> = game_session
+            .get_joined_players()
+            .iter()
+            .map(|_| game_session.session_bet)
+            .collect();
+        let total_refund_needed: u64 = refunds.iter().sum();
+        assert_eq!(total_refund_needed, 100000); // 10 players * 10000 tokens
 
-        // Simulate various vault balance scenarios
+        // refund_wager_handler now checks the vault covers every refund up front
         let vault_scenarios = vec![
-            ("Sufficient vault", total_refund_needed),
-            ("Insufficient vault", total_refund_needed / 2),
-            ("Empty vault", 0),
+            ("Sufficient vault", total_refund_needed, Some(0)),
+            ("Vault with a fee left over", total_refund_needed + 500, Some(500)),
+            ("Insufficient vault", total_refund_needed / 2, None),
+            ("Empty vault", 0, None),
         ];
 
-        for (scenario_name, vault_balance) in vault_scenarios {
-            println!("\n--- {} Scenario ---", scenario_name);
-            println!("Vault balance: {} tokens", vault_balance);
-            println!("Refund needed: {} tokens", total_refund_needed);
-
-            if vault_balance < total_refund_needed {
-                println!("❌ CRITICAL: Vault has insufficient funds for refund");
-                println!("   Shortfall: {} tokens", total_refund_needed - vault_balance);
-                println!("   This would cause transaction failures or partial refunds");
-            } else {
-                println!("✅ Vault has sufficient funds");
+        for (scenario_name, vault_balance, expected_residual) in vault_scenarios {
+            let result = vault_residual(vault_balance, &refunds);
+            match expected_residual {
+                Some(residual) => assert_eq!(result.unwrap(), residual, "{}", scenario_name),
+                None => assert_eq!(
+                    result.unwrap_err(),
+                    WagerError::InsufficientVaultBalance.into(),
+                    "{}",
+                    scenario_name
+                ),
             }
-
-            // The vulnerability: refund_wager_handler doesn't check vault balance
-            println!("   Current code: NO vault balance validation");
         }
+    }
 
-        assert_eq!(total_refund_needed, 100000); // 10 players * 10000 tokens
-        println!("\nVULNERABILITY CONFIRMED: No vault balance validation before refunds!");
+    #[test]
+    fn test_refunds_return_full_deposits() {
+        let mut game_session = GameSession {
+            game_mode: GameMode::PayToSpawnOneVsOne,
+            ..test_session()
+        };
+        let player_a = Pubkey::new_unique();
+        let player_b = Pubkey::new_unique();
+        game_session.team_a.players[0] = player_a;
+        game_session.team_b.players[0] = player_b;
+        game_session.add_spawns(0, 0).unwrap();
+        game_session.add_spawns(0, 0).unwrap();
+
+        // Spawn purchases are refunded along with the session bet
+        assert_eq!(
+            game_session.refunds().unwrap(),
+            vec![(player_a, 3000), (player_b, 1000)]
+        );
+        assert_eq!(vault_residual(4000, &[3000, 1000]).unwrap(), 0);
+
+        game_session.status = GameStatus::WaitingForPlayers;
+        assert_eq!(game_session.refunds().unwrap().len(), 2);
     }
 
     #[test]
     fn test_recommended_refund_validation() {
-        // Show what proper refund validation should look like
-        println!("\n=== Recommended Refund Validation Logic ===");
-
-        // Proper validation function that should exist
-        fn validate_refund_request(
-            game_status: &GameStatus,
-            vault_balance: u64,
-            total_refund_needed: u64,
-            already_refunded: bool,
-        ) -> std::result::Result<(), &'static str> {
-            // Check if game is in appropriate state for refund
-            if !matches!(game_status, GameStatus::WaitingForPlayers) {
-                return Err("Game not in refundable state");
-            }
-            
-            // Check if already refunded
-            if already_refunded {
-                return Err("Game already refunded");
-            }
-            
-            // Check vault balance
-            if vault_balance < total_refund_needed {
-                return Err("Insufficient vault balance");
-            }
-            
-            Ok(())
+        // Refunds need an unsettled session and a vault covering all of them
+        let mut game_session = GameSession {
+            session_bet: 5000,
+            status: GameStatus::WaitingForPlayers,
+            ..test_session()
+        };
+        game_session.team_a.players[0] = Pubkey::new_unique();
+        let amounts: Vec<u64> = game_session
+            .refunds()
+            .unwrap()
+            .into_iter()
+            .map(|(_, refund)| refund)
+            .collect();
+        assert_eq!(amounts, vec![5000]);
+
+        assert_eq!(vault_residual(10_000, &amounts).unwrap(), 5000);
+        assert_eq!(
+            vault_residual(3000, &amounts).unwrap_err(),
+            WagerError::InsufficientVaultBalance.into()
+        );
+
+        // A session is settled once refunded, so it cannot be refunded twice
+        game_session.status = GameStatus::Completed;
+        assert_eq!(
+            game_session.refunds().unwrap_err(),
+            WagerError::InvalidGameState.into()
+        );
+    }
+}
+
+#[cfg(test)]
+mod rating_tests {
+    use crate::rating::*;
+
+    #[test]
+    fn test_equal_ratings_move_by_half_k_factor() {
+        assert_eq!(expected_score_bps(1200, 1200), 5000);
+        assert_eq!(rating_change(1200, 1200, true), RATING_K_FACTOR / 2);
+        assert_eq!(rating_change(1200, 1200, false), -RATING_K_FACTOR / 2);
+    }
+
+    #[test]
+    fn test_expected_score_is_symmetric_and_interpolated() {
+        // 100 points apart is exactly the 4th table entry
+        assert_eq!(expected_score_bps(1200, 1300), 3599);
+        assert_eq!(expected_score_bps(1300, 1200), 10_000 - 3599);
+
+        // Halfway between the 0 and 25 point entries
+        let halfway = expected_score_bps(1200, 1212);
+        assert!(halfway < 5000 && halfway > 4641);
+
+        // Differences beyond the table are clamped
+        assert_eq!(expected_score_bps(0, 5000), expected_score_bps(1000, 1800));
+    }
+
+    #[test]
+    fn test_upset_moves_rating_more_than_expected_win() {
+        let favourite_win = rating_change(1600, 1200, true);
+        let underdog_win = rating_change(1200, 1600, true);
+        assert!(favourite_win < underdog_win);
+        assert!(favourite_win > 0);
+        assert_eq!(team_average_rating(&[1000, 1200, 1400]), 1200);
+        assert_eq!(team_average_rating(&[]), DEFAULT_RATING);
+    }
+}
+
+#[cfg(test)]
+mod profile_tests {
+    use crate::errors::WagerError;
+    use crate::rating::{rating_change, DEFAULT_RATING, RATING_K_FACTOR};
+    use crate::state::*;
+    use crate::test_utils::test_session;
+    use anchor_lang::prelude::*;
+
+    /// Both teams at the default rating
+    const EVEN: [u32; 2] = [DEFAULT_RATING, DEFAULT_RATING];
+
+    fn profile() -> PlayerProfile {
+        let mut profile = PlayerProfile {
+            player: Pubkey::default(),
+            games_played: 0,
+            wins: 0,
+            losses: 0,
+            total_wagered: 0,
+            total_won: 0,
+            total_kills: 0,
+            total_deaths: 0,
+            total_assists: 0,
+            rating: 0,
+            bump: 0,
+        };
+        profile.initialize(Pubkey::new_unique(), 255);
+        profile
+    }
+
+    /// Settled 3v3 won by team A, whose second player forfeited
+    fn settled_with_forfeit() -> (GameSession, SessionResult) {
+        let mut game_session = GameSession {
+            game_mode: GameMode::WinnerTakesAllThreeVsThree,
+            ..test_session()
+        };
+        for slot in 0..3 {
+            game_session.team_a.players[slot] = Pubkey::new_unique();
+            game_session.team_b.players[slot] = Pubkey::new_unique();
         }
+        game_session.team_a.player_kills[1] = 2;
+        game_session.team_a.player_forfeited[1] = true;
+
+        let mut session_result = SessionResult {
+            session_id: String::new(),
+            game_session: Pubkey::default(),
+            game_mode: game_session.game_mode,
+            winning_team: None,
+            team_a: TeamResult::default(),
+            team_b: TeamResult::default(),
+            total_paid: 0,
+            fee_taken: 0,
+            settled_at: 0,
+            bump: 0,
+        };
+        session_result.record_scoreboard(Pubkey::new_unique(), &game_session, Some(0));
+        session_result
+            .record_payout(game_session.team_a.players[0], 3000)
+            .unwrap();
+        session_result
+            .record_payout(game_session.team_a.players[2], 3000)
+            .unwrap();
+        (game_session, session_result)
+    }
 
-        // Test the validation logic
-        let test_cases = vec![
-            (GameStatus::WaitingForPlayers, 10000, 5000, false, "Should succeed - valid refund"),
-            (GameStatus::InProgress, 10000, 5000, false, "Should fail - game in progress"),
-            (GameStatus::Completed, 10000, 5000, false, "Should fail - game completed"),
-            (GameStatus::WaitingForPlayers, 3000, 5000, false, "Should fail - insufficient vault"),
-            (GameStatus::WaitingForPlayers, 10000, 5000, true, "Should fail - already refunded"),
-        ];
+    #[test]
+    fn test_record_wager_and_game_add_up() {
+        let mut profile = profile();
+        assert_eq!(profile.rating, DEFAULT_RATING);
+
+        profile.record_wager(1000).unwrap();
+        profile.record_wager(500).unwrap();
+        assert_eq!(profile.total_wagered, 1500);
+
+        profile.record_game(Some(true), 3, 1, 2, 2000).unwrap();
+        profile.record_game(Some(false), 1, 4, 0, 0).unwrap();
+        profile.record_game(None, 2, 2, 1, 700).unwrap();
+        assert_eq!(profile.games_played, 3);
+        assert_eq!(profile.wins, 1);
+        assert_eq!(profile.losses, 1);
+        assert_eq!(profile.total_won, 2700);
+        assert_eq!(profile.total_kills, 6);
+        assert_eq!(profile.total_deaths, 7);
+        assert_eq!(profile.total_assists, 3);
 
-        for (status, vault_balance, refund_needed, already_refunded, description) in test_cases {
-            let result = validate_refund_request(&status, vault_balance, refund_needed, already_refunded);
-            
-            match result {
-                Ok(()) => println!("✅ {}: Refund allowed", description),
-                Err(reason) => println!("❌ {}: Refund denied - {}", description, reason),
-            }
+        assert_eq!(
+            profile.record_wager(u64::MAX).unwrap_err(),
+            WagerError::ArithmeticError.into()
+        );
+    }
+
+    #[test]
+    fn test_forfeited_winner_is_recorded_as_a_loss() {
+        let (game_session, session_result) = settled_with_forfeit();
+
+        let mut winner = profile();
+        assert_eq!(
+            winner
+                .record_session(&game_session, &session_result, 0, 0, EVEN)
+                .unwrap(),
+            Some(true)
+        );
+        assert_eq!((winner.wins, winner.losses), (1, 0));
+        assert_eq!(winner.total_wagered, 1000);
+        assert_eq!(winner.total_won, 3000);
+
+        let mut quitter = profile();
+        assert_eq!(
+            quitter
+                .record_session(&game_session, &session_result, 0, 1, EVEN)
+                .unwrap(),
+            Some(false)
+        );
+        assert_eq!((quitter.wins, quitter.losses), (0, 1));
+        assert_eq!(quitter.games_played, 1);
+        assert_eq!(quitter.total_wagered, 1000);
+        assert_eq!(quitter.total_won, 0);
+        assert_eq!(quitter.total_kills, 2);
+
+        let mut loser = profile();
+        assert_eq!(
+            loser
+                .record_session(&game_session, &session_result, 1, 0, EVEN)
+                .unwrap(),
+            Some(false)
+        );
+
+        // Pay-to-spawn sessions have no winner, forfeits included
+        let mut pay_to_spawn_result = session_result.clone();
+        pay_to_spawn_result.winning_team = None;
+        let mut pay_to_spawn_quitter = profile();
+        assert_eq!(
+            pay_to_spawn_quitter
+                .record_session(&game_session, &pay_to_spawn_result, 0, 1, EVEN)
+                .unwrap(),
+            None
+        );
+        assert_eq!(pay_to_spawn_quitter.losses, 0);
+    }
+
+    #[test]
+    fn test_forfeited_winner_loses_rating() {
+        let (game_session, session_result) = settled_with_forfeit();
+        let average_ratings = [1300, 1200];
+
+        let mut winner = profile();
+        winner
+            .record_session(&game_session, &session_result, 0, 0, average_ratings)
+            .unwrap();
+        assert_eq!(
+            winner.rating as i64,
+            DEFAULT_RATING as i64 + rating_change(1300, 1200, true)
+        );
+
+        // Rated as a loser of the team it left
+        let mut quitter = profile();
+        quitter
+            .record_session(&game_session, &session_result, 0, 1, average_ratings)
+            .unwrap();
+        assert_eq!(
+            quitter.rating as i64,
+            DEFAULT_RATING as i64 + rating_change(1300, 1200, false)
+        );
+        assert!(quitter.rating < DEFAULT_RATING);
+
+        let mut loser = profile();
+        loser
+            .record_session(&game_session, &session_result, 1, 0, average_ratings)
+            .unwrap();
+        assert!(DEFAULT_RATING - loser.rating < RATING_K_FACTOR as u32 / 2);
+
+        // No rating change without a winning team
+        let mut pay_to_spawn_result = session_result.clone();
+        pay_to_spawn_result.winning_team = None;
+        let mut pay_to_spawn_quitter = profile();
+        pay_to_spawn_quitter
+            .record_session(&game_session, &pay_to_spawn_result, 0, 1, average_ratings)
+            .unwrap();
+        assert_eq!(pay_to_spawn_quitter.rating, DEFAULT_RATING);
+    }
+}
+
+#[cfg(test)]
+mod payout_tests {
+    use crate::payout::{split_pot, vault_residual};
+    use crate::state::*;
+
+    #[test]
+    fn test_vault_residual_covers_all_payouts() {
+        assert_eq!(vault_residual(1000, &[300, 300, 300]).unwrap(), 100);
+        assert_eq!(vault_residual(900, &[300, 300, 300]).unwrap(), 0);
+        assert!(vault_residual(899, &[300, 300, 300]).is_err());
+        assert!(vault_residual(u64::MAX, &[u64::MAX, 1]).is_err());
+        assert_eq!(vault_residual(0, &[]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_split_pot_pays_out_exactly_the_pot() {
+        let (shares, remainder) = split_pot(1000, &[3, 3, 3]);
+        assert_eq!(shares, vec![333, 333, 333]);
+        assert_eq!(remainder, 1);
+        assert_eq!(shares.iter().sum::<u64>() + remainder, 1000);
+
+        let (shares, remainder) = split_pot(u64::MAX, &[u64::MAX, 1]);
+        assert_eq!(shares.iter().sum::<u64>() + remainder, u64::MAX);
+    }
+
+    #[test]
+    fn test_split_pot_without_scores_splits_evenly() {
+        let (shares, remainder) = split_pot(1000, &[0, 0]);
+        assert_eq!(shares, vec![500, 500]);
+        assert_eq!(remainder, 0);
+
+        let (shares, remainder) = split_pot(1000, &[]);
+        assert!(shares.is_empty());
+        assert_eq!(remainder, 1000);
+    }
+
+    #[test]
+    fn test_score_weighs_kills_against_deaths() {
+        let config = PayoutConfig::default();
+        let team = Team {
+            player_kills: [5, 0, 0, 0, 0],
+            player_deaths: [2, 10, 0, 0, 0],
+            ..Default::default()
+        };
+        assert_eq!(config.score(&team, 0), 5 * DEFAULT_KILL_WEIGHT as u64 - 2);
+        assert_eq!(config.score(&team, 1), 0);
+    }
+
+    #[test]
+    fn test_score_counts_assists_and_optional_team_kill_penalty() {
+        let team = Team {
+            player_kills: [2, 0, 0, 0, 0],
+            player_assists: [3, 0, 0, 0, 0],
+            player_team_kills: [1, 0, 0, 0, 0],
+            ..Default::default()
+        };
+
+        let config = PayoutConfig::default();
+        assert_eq!(
+            config.score(&team, 0),
+            2 * DEFAULT_KILL_WEIGHT as u64 + 3 * DEFAULT_ASSIST_WEIGHT as u64
+        );
+
+        let config = PayoutConfig {
+            team_kill_penalty: 4,
+            ..PayoutConfig::default()
+        };
+        assert_eq!(
+            config.score(&team, 0),
+            2 * DEFAULT_KILL_WEIGHT as u64 + 3 * DEFAULT_ASSIST_WEIGHT as u64 - 4
+        );
+    }
+}
+
+#[cfg(test)]
+mod session_result_tests {
+    use crate::errors::WagerError;
+    use crate::state::*;
+    use crate::test_utils::test_session;
+    use anchor_lang::prelude::*;
+
+    fn empty_result() -> SessionResult {
+        SessionResult {
+            session_id: String::new(),
+            game_session: Pubkey::default(),
+            game_mode: GameMode::WinnerTakesAllOneVsOne,
+            winning_team: None,
+            team_a: TeamResult::default(),
+            team_b: TeamResult::default(),
+            total_paid: 0,
+            fee_taken: 0,
+            settled_at: 0,
+            bump: 0,
+        }
+    }
+
+    /// 1v1 session after a kill by team A
+    fn played_session() -> GameSession {
+        let mut game_session = GameSession {
+            session_id: "result".to_string(),
+            ..test_session()
+        };
+        game_session.team_a.players[0] = Pubkey::new_unique();
+        game_session.team_a.player_kills[0] = 1;
+        game_session.team_a.player_spawns[0] = 10;
+        game_session.team_b.players[0] = Pubkey::new_unique();
+        game_session.team_b.player_spawns[0] = 9;
+        game_session
+    }
+
+    #[test]
+    fn test_record_scoreboard_copies_the_final_standing() {
+        let game_session = played_session();
+        let game_session_key = Pubkey::new_unique();
+        let mut session_result = empty_result();
+        session_result.record_scoreboard(game_session_key, &game_session, Some(0));
+
+        assert_eq!(session_result.session_id, "result");
+        assert_eq!(session_result.game_session, game_session_key);
+        assert!(session_result.game_mode == GameMode::WinnerTakesAllOneVsOne);
+        assert_eq!(session_result.winning_team, Some(0));
+        assert_eq!(session_result.team_a.players, game_session.team_a.players);
+        assert_eq!(session_result.team_a.player_kills[0], 1);
+        assert_eq!(session_result.team_a.player_spawns[0], 10);
+        assert_eq!(session_result.team_b.players, game_session.team_b.players);
+        assert_eq!(session_result.team_b.player_spawns[0], 9);
+        assert_eq!(session_result.team_a.payouts, [0; 5]);
+        assert_eq!(session_result.total_paid, 0);
+    }
+
+    #[test]
+    fn test_record_payout_credits_the_player_slot() {
+        let game_session = played_session();
+        let winner = game_session.team_a.players[0];
+        let loser = game_session.team_b.players[0];
+        let mut session_result = empty_result();
+        session_result.record_scoreboard(Pubkey::new_unique(), &game_session, None);
+
+        session_result.record_payout(winner, 1500).unwrap();
+        session_result.record_payout(loser, 400).unwrap();
+        session_result.record_payout(winner, 100).unwrap();
+        assert_eq!(session_result.team_a.payouts[0], 1600);
+        assert_eq!(session_result.team_b.payouts[0], 400);
+        assert_eq!(session_result.total_paid, 2000);
+
+        assert_eq!(
+            session_result
+                .record_payout(Pubkey::new_unique(), 1)
+                .unwrap_err(),
+            WagerError::PlayerNotFound.into()
+        );
+        assert_eq!(
+            session_result.record_payout(winner, u64::MAX).unwrap_err(),
+            WagerError::ArithmeticError.into()
+        );
+        assert_eq!(session_result.total_paid, 2000);
+    }
+}
+
+#[cfg(test)]
+mod kill_tests {
+    use super::*;
+    use crate::state::*;
+    use crate::test_utils::test_session;
+
+    fn one_vs_one_session() -> GameSession {
+        GameSession {
+            session_id: "kill_test".to_string(),
+            game_mode: GameMode::PayToSpawnOneVsOne,
+            team_a: Team {
+                players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
+                player_spawns: [10, 0, 0, 0, 0],
+                ..Default::default()
+            },
+            team_b: Team {
+                players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
+                player_spawns: [10, 0, 0, 0, 0],
+                ..Default::default()
+            },
+            ..test_session()
+        }
+    }
+
+    fn kill(kind: KillKind, killer_team: u8, killer_index: u8, victim_team: u8, victim_index: u8) -> KillEvent {
+        KillEvent {
+            kind,
+            killer_team,
+            killer_index,
+            victim_team,
+            victim_index,
         }
+    }
+
+    #[test]
+    fn test_kill_by_index_updates_both_players() {
+        let mut game_session = one_vs_one_session();
+        game_session.apply_kill_event(&kill(KillKind::Normal, 0, 0, 1, 0)).unwrap();
+
+        assert_eq!(game_session.team_a.player_kills[0], 1);
+        assert_eq!(game_session.team_b.player_spawns[0], 9);
+        assert_eq!(game_session.team_b.player_deaths[0], 1);
+    }
+
+    #[test]
+    fn test_kill_by_index_rejects_empty_and_out_of_range_slots() {
+        let mut game_session = one_vs_one_session();
+        assert!(game_session.apply_kill_event(&kill(KillKind::Normal, 0, 1, 1, 0)).is_err());
+        assert!(game_session.apply_kill_event(&kill(KillKind::Normal, 0, 0, 1, 5)).is_err());
+        assert!(game_session.apply_kill_event(&kill(KillKind::Normal, 2, 0, 1, 0)).is_err());
+
+        // Nothing was applied by the rejected kills
+        assert_eq!(game_session.team_a.player_kills[0], 0);
+        assert_eq!(game_session.team_b.player_spawns[0], 10);
+    }
+
+    #[test]
+    fn test_assist_credits_helper_without_a_death() {
+        let mut game_session = one_vs_one_session();
+        game_session.apply_kill_event(&kill(KillKind::Assist, 0, 0, 1, 0)).unwrap();
+
+        assert_eq!(game_session.team_a.player_assists[0], 1);
+        assert_eq!(game_session.team_a.player_kills[0], 0);
+        assert_eq!(game_session.team_b.player_spawns[0], 10);
+        assert_eq!(game_session.team_b.player_deaths[0], 0);
+    }
+
+    #[test]
+    fn test_suicide_and_environmental_deaths_credit_no_kill() {
+        let mut game_session = one_vs_one_session();
+        game_session.apply_kill_event(&kill(KillKind::Suicide, 1, 0, 1, 0)).unwrap();
+        // Killer fields of an environmental death may point anywhere
+        game_session.apply_kill_event(&kill(KillKind::Environmental, 0, 4, 1, 0)).unwrap();
+        game_session.apply_kill_event(&kill(KillKind::Environmental, 7, 9, 1, 0)).unwrap();
+
+        assert_eq!(game_session.team_b.player_kills[0], 0);
+        assert_eq!(game_session.team_a.player_kills[0], 0);
+        assert_eq!(game_session.team_b.player_spawns[0], 7);
+        assert_eq!(game_session.team_b.player_deaths[0], 3);
+    }
 
-        println!("\nCURRENT VULNERABILITY: No such validation exists in refund_wager_handler");
-        println!("IMPACT: Inappropriate refunds can be processed without validation");
+    #[test]
+    fn test_space_fits_the_largest_session() {
+        let game_session = GameSession {
+            session_id: "a".repeat(MAX_SESSION_ID_LEN),
+            rating_band: Some(RatingBand { min_rating: 0, max_rating: u32::MAX }),
+            payout_config: PayoutConfig {
+                remainder_destination: Some(Pubkey::new_unique()),
+                ..PayoutConfig::default()
+            },
+            match_log: Some(MatchLog::default()),
+            side_pool: SideBetPool {
+                outcome: SidePoolOutcome::TeamWon(0),
+                ..SideBetPool::default()
+            },
+            series: Some(Series::new(MAX_SERIES_LENGTH).unwrap()),
+            forfeit_winner: Some(0),
+            ..test_session()
+        };
+        assert_eq!(8 + game_session.try_to_vec().unwrap().len(), GameSession::SPACE);
+    }
+
+    #[test]
+    fn test_team_kill_is_tracked_separately() {
+        let mut game_session = one_vs_one_session();
+        game_session.team_a.players[1] = Pubkey::new_unique();
+        game_session.team_a.player_spawns[1] = 10;
+
+        let killer = game_session.team_a.players[0];
+        let victim = game_session.team_a.players[1];
+        game_session.add_kill(0, killer, 0, victim).unwrap();
+
+        assert_eq!(game_session.team_a.player_team_kills[0], 1);
+        assert_eq!(game_session.team_a.player_kills[0], 0);
+        assert_eq!(game_session.team_a.player_spawns[1], 9);
+        assert_eq!(game_session.team_a.player_deaths[1], 1);
+    }
+
+    #[test]
+    fn test_kill_kind_must_match_players() {
+        let mut game_session = one_vs_one_session();
+        assert!(game_session.apply_kill_event(&kill(KillKind::Normal, 0, 0, 0, 0)).is_err());
+        assert!(game_session.apply_kill_event(&kill(KillKind::Assist, 1, 0, 1, 0)).is_err());
+        assert!(game_session.apply_kill_event(&kill(KillKind::Suicide, 0, 0, 1, 0)).is_err());
+        assert!(game_session.apply_kill_event(&kill(KillKind::TeamKill, 0, 0, 0, 0)).is_err());
+        assert!(game_session.apply_kill_event(&kill(KillKind::TeamKill, 0, 0, 1, 0)).is_err());
+
+        assert_eq!(game_session.team_a.player_spawns[0], 10);
+        assert_eq!(game_session.team_b.player_spawns[0], 10);
+    }
+}
+
+#[cfg(test)]
+mod match_log_tests {
+    use crate::match_log::*;
+    use crate::state::*;
+    use crate::test_utils::test_session;
+
+    fn events(count: u8) -> Vec<Vec<u8>> {
+        (0..count).map(|i| vec![i, i.wrapping_mul(7), 42]).collect()
+    }
+
+    fn logged_session() -> GameSession {
+        GameSession {
+            session_id: "log_test".to_string(),
+            match_log: Some(MatchLog::default()),
+            ..test_session()
+        }
+    }
+
+    #[test]
+    fn test_every_event_verifies_against_the_root() {
+        for count in 1..=7 {
+            let leaves: Vec<[u8; 32]> = events(count).iter().map(|e| leaf_hash(e)).collect();
+            let root = merkle_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                assert!(verify_proof(&root, leaf, &merkle_proof(&leaves, index)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampered_event_does_not_verify() {
+        let events = events(5);
+        let leaves: Vec<[u8; 32]> = events.iter().map(|e| leaf_hash(e)).collect();
+        let root = merkle_root(&leaves);
+        let proof = merkle_proof(&leaves, 2);
+
+        assert!(!verify_proof(&root, &leaf_hash(&[2, 14, 43]), &proof));
+        assert!(!verify_proof(&root, &leaves[3], &proof));
+    }
+
+    #[test]
+    fn test_session_verifies_events_against_committed_root() {
+        let events = events(4);
+        let leaves: Vec<[u8; 32]> = events.iter().map(|e| leaf_hash(e)).collect();
+        let mut game_session = logged_session();
+        game_session
+            .commit_match_log(merkle_root(&leaves), 4, true)
+            .unwrap();
+
+        assert!(game_session.verify_match_event(&events[1], &merkle_proof(&leaves, 1)));
+        assert!(!game_session.verify_match_event(&events[1], &merkle_proof(&leaves, 0)));
+    }
+
+    #[test]
+    fn test_settlement_waits_for_final_root() {
+        let mut game_session = logged_session();
+        assert!(!game_session.has_final_match_log());
+
+        game_session.commit_match_log([1; 32], 10, false).unwrap();
+        assert!(!game_session.has_final_match_log());
+
+        // A log cannot shrink
+        assert!(game_session.commit_match_log([2; 32], 9, false).is_err());
+
+        game_session.commit_match_log([3; 32], 12, true).unwrap();
+        assert!(game_session.has_final_match_log());
+        assert_eq!(game_session.match_log.unwrap().commits, 2);
+
+        // Nothing can be committed after the final root
+        assert!(game_session.commit_match_log([4; 32], 13, true).is_err());
+    }
+
+    #[test]
+    fn test_sessions_without_match_log() {
+        let mut game_session = logged_session();
+        game_session.match_log = None;
+
+        assert!(game_session.has_final_match_log());
+        assert!(game_session.commit_match_log([1; 32], 1, true).is_err());
+        assert!(!game_session.verify_match_event(&[1], &[]));
+    }
+}
+
+#[cfg(test)]
+mod side_bet_tests {
+    use crate::state::*;
+
+    fn pool(team_a: u64, team_b: u64, outcome: SidePoolOutcome) -> SideBetPool {
+        SideBetPool {
+            team_totals: [team_a, team_b],
+            outcome,
+        }
+    }
+
+    #[test]
+    fn test_winners_share_the_whole_pool() {
+        let pool = pool(300, 700, SidePoolOutcome::TeamWon(0));
+
+        assert_eq!(pool.payout(0, 100).unwrap(), 333);
+        assert_eq!(pool.payout(0, 200).unwrap(), 666);
+        assert_eq!(pool.payout(1, 700).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_bets_are_refunded_on_cancellation_or_without_winning_bets() {
+        let cancelled = pool(300, 700, SidePoolOutcome::Cancelled);
+        assert_eq!(cancelled.payout(0, 100).unwrap(), 100);
+        assert_eq!(cancelled.payout(1, 700).unwrap(), 700);
+
+        let unbacked = pool(0, 700, SidePoolOutcome::TeamWon(0));
+        assert_eq!(unbacked.payout(1, 700).unwrap(), 700);
+    }
+
+    #[test]
+    fn test_open_pool_cannot_be_claimed() {
+        let pool = pool(300, 700, SidePoolOutcome::Open);
+        assert!(pool.payout(0, 100).is_err());
+    }
+
+    #[test]
+    fn test_add_bet_tracks_team_totals() {
+        let mut pool = SideBetPool::default();
+        pool.add_bet(0, 100).unwrap();
+        pool.add_bet(1, 50).unwrap();
+        pool.add_bet(0, 25).unwrap();
+
+        assert_eq!(pool.team_totals, [125, 50]);
+        assert!(pool.add_bet(2, 10).is_err());
+        assert!(pool.add_bet(0, u64::MAX).is_err());
+    }
+}
+
+#[cfg(test)]
+mod tournament_tests {
+    use crate::errors::WagerError;
+    use crate::state::*;
+    use anchor_lang::prelude::*;
+
+    fn tournament(bracket_size: u8) -> Tournament {
+        Tournament {
+            tournament_id: "cup".to_string(),
+            authority: Pubkey::new_unique(),
+            entry_fee: 100,
+            bracket_size,
+            entrant_count: 0,
+            bracket: [Pubkey::default(); 2 * MAX_TOURNAMENT_PLAYERS - 1],
+            match_sessions: [Pubkey::default(); MAX_TOURNAMENT_PLAYERS - 1],
+            status: TournamentStatus::Registering,
+            prize_pool: 0,
+            bump: 0,
+            vault_bump: 0,
+        }
+    }
+
+    fn filled_tournament(bracket_size: u8) -> (Tournament, Vec<Pubkey>) {
+        let mut tournament = tournament(bracket_size);
+        let entrants: Vec<Pubkey> = (0..bracket_size).map(|_| Pubkey::new_unique()).collect();
+        for entrant in &entrants {
+            tournament.add_entrant(*entrant).unwrap();
+        }
+        (tournament, entrants)
+    }
+
+    const LIMITS: BetLimits = BetLimits {
+        min_bet: 100,
+        max_bet: 1_000_000,
+    };
+
+    #[test]
+    fn test_validate_tournament() {
+        assert!(Tournament::validate("cup", 100, 8, &LIMITS).is_ok());
+        assert!(Tournament::validate("", 100, 8, &LIMITS).is_err());
+        assert!(Tournament::validate("toolong", 100, 8, &LIMITS).is_err());
+        assert!(Tournament::validate("cup", 100, 1, &LIMITS).is_err());
+        assert!(Tournament::validate("cup", 100, 6, &LIMITS).is_err());
+        assert!(Tournament::validate("cup", 100, 32, &LIMITS).is_err());
+    }
+
+    #[test]
+    fn test_entry_fee_follows_the_bet_limits() {
+        for entry_fee in [0, 99, 1_000_001, u64::MAX] {
+            assert_eq!(
+                Tournament::validate("cup", entry_fee, 8, &LIMITS).unwrap_err(),
+                WagerError::InvalidBetAmount.into()
+            );
+        }
+        assert!(Tournament::validate("cup", 1_000_000, 8, &LIMITS).is_ok());
+    }
+
+    #[test]
+    fn test_cancel_refunds_every_entrant() {
+        // A bracket that never fills
+        let mut tournament = tournament(4);
+        let entrant = Pubkey::new_unique();
+        tournament.add_entrant(entrant).unwrap();
+        assert_eq!(tournament.cancel().unwrap(), vec![(entrant, 100)]);
+        assert!(tournament.status == TournamentStatus::Cancelled);
+        assert_eq!(tournament.prize_pool, 0);
+        assert!(tournament.cancel().is_err());
+        assert!(tournament.add_entrant(Pubkey::new_unique()).is_err());
+
+        // A bracket stuck on a match that will never be settled
+        let (mut tournament, entrants) = filled_tournament(4);
+        tournament.advance(0, 0, entrants[0]).unwrap();
+        let refunds = tournament.cancel().unwrap();
+        assert_eq!(refunds.len(), 4);
+        assert!(refunds.iter().all(|(_, refund)| *refund == 100));
+        assert!(tournament.advance(0, 1, entrants[2]).is_err());
+
+        // Paid out tournaments stay paid out
+        let (mut tournament, entrants) = filled_tournament(2);
+        tournament.advance(0, 0, entrants[1]).unwrap();
+        assert!(tournament.cancel().is_err());
+    }
+
+    #[test]
+    fn test_registration_fills_bracket_and_prize_pool() {
+        let mut tournament = tournament(4);
+        let player = Pubkey::new_unique();
+        tournament.add_entrant(player).unwrap();
+        assert!(tournament.add_entrant(player).is_err());
+
+        // Matches cannot start before the bracket is full
+        assert!(tournament.match_players(0, 0).is_err());
+
+        let (tournament, entrants) = filled_tournament(4);
+        assert!(tournament.status == TournamentStatus::InProgress);
+        assert_eq!(tournament.prize_pool, 400);
+        assert_eq!(tournament.match_players(0, 1).unwrap(), (entrants[2], entrants[3]));
+    }
+
+    #[test]
+    fn test_winners_advance_to_the_final() {
+        let (mut tournament, entrants) = filled_tournament(4);
+        assert_eq!(tournament.rounds(), 2);
+
+        // The final waits for both semi finals
+        assert!(tournament.match_players(1, 0).is_err());
+        assert_eq!(tournament.advance(0, 0, entrants[1]).unwrap(), None);
+        assert!(tournament.match_players(1, 0).is_err());
+        assert_eq!(tournament.advance(0, 1, entrants[2]).unwrap(), None);
+        assert_eq!(tournament.match_players(1, 0).unwrap(), (entrants[1], entrants[2]));
+
+        assert_eq!(tournament.advance(1, 0, entrants[2]).unwrap(), Some(entrants[2]));
+        assert!(tournament.status == TournamentStatus::Completed);
+    }
+
+    #[test]
+    fn test_advance_rejects_outsiders_and_repeats() {
+        let (mut tournament, entrants) = filled_tournament(4);
+        assert!(tournament.advance(0, 0, entrants[2]).is_err());
+        assert!(tournament.advance(0, 2, entrants[0]).is_err());
+        assert!(tournament.advance(2, 0, entrants[0]).is_err());
+
+        tournament.advance(0, 0, entrants[0]).unwrap();
+        assert!(tournament.advance(0, 0, entrants[1]).is_err());
+    }
+
+    #[test]
+    fn test_every_match_records_one_session() {
+        let (mut tournament, entrants) = filled_tournament(8);
+        let numbers: Vec<usize> = [(0, 0), (0, 3), (1, 0), (1, 1), (2, 0)]
+            .iter()
+            .map(|(round, match_index)| tournament.match_number(*round, *match_index))
+            .collect();
+        assert_eq!(numbers, vec![0, 3, 4, 5, 6]);
+
+        let session = Pubkey::new_unique();
+        tournament.set_match_session(0, 1, session).unwrap();
+        assert_eq!(tournament.match_sessions[1], session);
+        assert!(tournament.set_match_session(0, 1, Pubkey::new_unique()).is_err());
+
+        // Later rounds wait for their players
+        assert!(tournament.set_match_session(1, 0, Pubkey::new_unique()).is_err());
+        tournament.advance(0, 0, entrants[0]).unwrap();
+        tournament.advance(0, 1, entrants[3]).unwrap();
+        assert!(tournament.set_match_session(1, 0, Pubkey::new_unique()).is_ok());
+    }
+
+    #[test]
+    fn test_match_session_ids_are_unique_and_fit_a_session() {
+        let (tournament, _) = filled_tournament(16);
+        assert_eq!(tournament.match_session_id(0, 7), "cup-07");
+        assert_eq!(tournament.match_session_id(3, 0), "cup-30");
+
+        let mut longest = tournament.clone();
+        longest.tournament_id = "abcdef".to_string();
+        assert!(longest.match_session_id(3, 7).len() <= 10);
+    }
+}
+
+#[cfg(test)]
+mod series_tests {
+    use crate::errors::WagerError;
+    use crate::state::*;
+    use crate::test_utils::test_session;
+    use anchor_lang::prelude::*;
+
+    #[test]
+    fn test_series_length_must_be_odd_and_bounded() {
+        assert!(Series::new(3).is_ok());
+        assert!(Series::new(MAX_SERIES_LENGTH).is_ok());
+        assert!(Series::new(0).is_err());
+        assert!(Series::new(4).is_err());
+        assert!(Series::new(MAX_SERIES_LENGTH + 2).is_err());
+    }
+
+    #[test]
+    fn test_series_is_clinched_by_majority_of_rounds() {
+        let mut series = Series::new(5).unwrap();
+        series.record_round(0).unwrap();
+        series.record_round(1).unwrap();
+        series.record_round(1).unwrap();
+        assert_eq!(series.winner(), None);
+
+        series.record_round(1).unwrap();
+        assert_eq!(series.winner(), Some(1));
+        assert_eq!(series.rounds_played, 4);
+        assert_eq!(&series.round_winners[..4], &[0, 1, 1, 1]);
+
+        // No rounds are played once the series is decided
+        assert!(series.record_round(0).is_err());
+    }
+
+    #[test]
+    fn test_players_respawn_for_the_next_round() {
+        let mut game_session = GameSession {
+            series: Some(Series::new(3).unwrap()),
+            ..test_session()
+        };
+        let player_a = Pubkey::new_unique();
+        let player_b = Pubkey::new_unique();
+        game_session.team_a.players[0] = player_a;
+        game_session.team_b.players[0] = player_b;
+        game_session.team_a.player_spawns[0] = 1;
+
+        // Round 1 uses up the last spawn of player A
+        game_session.add_kill(1, player_b, 0, player_a).unwrap();
+        assert_eq!(
+            game_session.add_kill(1, player_b, 0, player_a).unwrap_err(),
+            WagerError::PlayerHasNoSpawns.into()
+        );
+        game_session.record_series_round(1).unwrap();
+        assert_eq!(game_session.team_a.player_spawns[0], INITIAL_SPAWNS);
+
+        // Round 2 kills are recorded again, stats carry over
+        game_session.add_kill(1, player_b, 0, player_a).unwrap();
+        assert_eq!(game_session.team_a.player_spawns[0], INITIAL_SPAWNS - 1);
+        assert_eq!(game_session.team_b.player_kills[0], 2);
+        assert_eq!(
+            game_session.check_can_settle_for(1).unwrap_err(),
+            WagerError::SeriesNotDecided.into()
+        );
+
+        // No respawn once the series is clinched
+        game_session.record_series_round(1).unwrap();
+        assert_eq!(game_session.team_a.player_spawns[0], INITIAL_SPAWNS - 1);
+        assert!(game_session.check_can_settle_for(1).is_ok());
+        assert_eq!(
+            game_session.check_can_settle_for(0).unwrap_err(),
+            WagerError::SeriesNotDecided.into()
+        );
+    }
+
+    #[test]
+    fn test_invalid_round_winner_is_rejected() {
+        let mut series = Series::new(3).unwrap();
+        assert!(series.record_round(2).is_err());
+        assert_eq!(series.rounds_played, 0);
+    }
+}
+
+#[cfg(test)]
+mod template_tests {
+    use crate::state::*;
+    use crate::test_utils::test_session;
+    use anchor_lang::prelude::*;
+
+    fn lobby_template(template_id: &str) -> LobbyTemplate {
+        LobbyTemplate {
+            template_id: template_id.to_string(),
+            authority: Pubkey::new_unique(),
+            game_mode: GameMode::WinnerTakesAllOneVsOne,
+            session_bet: 1000,
+            mint: Pubkey::new_unique(),
+            fee_bps: 250,
+            join_timeout: 600,
+            next_session_index: 0,
+            bet_limits: BetLimits {
+                min_bet: 500,
+                max_bet: 5000,
+            },
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(lobby_template("duel").validate().is_ok());
+        assert!(lobby_template("").validate().is_err());
+        assert!(lobby_template("duels").validate().is_err());
+
+        let mut template = lobby_template("duel");
+        template.fee_bps = BPS_DENOMINATOR;
+        assert!(template.validate().is_err());
+
+        let mut template = lobby_template("duel");
+        template.join_timeout = -1;
+        assert!(template.validate().is_err());
+
+        let mut template = lobby_template("duel");
+        template.session_bet = 0;
+        assert!(template.validate().is_err());
+
+        let mut template = lobby_template("duel");
+        template.session_bet = 5001;
+        assert!(template.validate().is_err());
+
+        let mut template = lobby_template("duel");
+        template.bet_limits.min_bet = 0;
+        template.session_bet = 0;
+        assert!(template.validate().is_err());
+    }
+
+    #[test]
+    fn test_session_ids_follow_the_counter() {
+        let mut template = lobby_template("duel");
+        assert_eq!(template.next_session_id(), "duel000000");
+        template.advance_session_index().unwrap();
+        template.advance_session_index().unwrap();
+        assert_eq!(template.next_session_id(), "duel000002");
+
+        template.next_session_index = MAX_TEMPLATE_SESSIONS;
+        assert_eq!(template.next_session_id(), "duelffffff");
+        assert!(template.advance_session_index().is_err());
+    }
+
+    #[test]
+    fn test_fee_is_taken_from_payouts() {
+        let mut game_session = GameSession {
+            session_id: "duel000000".to_string(),
+            status: GameStatus::WaitingForPlayers,
+            ..test_session()
+        };
+        assert_eq!(game_session.fee_on(2000), 0);
+
+        game_session.fee_bps = 250;
+        assert_eq!(game_session.fee_on(2000), 50);
+        assert_eq!(game_session.fee_on(39), 0);
+        assert_eq!(game_session.fee_on(u64::MAX), u64::MAX / 40);
+    }
+}
+
+#[cfg(test)]
+mod session_counter_tests {
+    use crate::state::*;
+    use anchor_lang::prelude::*;
+
+    #[test]
+    fn test_counter_hands_out_consecutive_indexes() {
+        let authority = Pubkey::new_unique();
+        let mut counter = SessionCounter {
+            authority: Pubkey::default(),
+            next_index: 0,
+            bump: 0,
+        };
+
+        assert_eq!(counter.claim_index(authority, 254).unwrap(), 0);
+        assert_eq!(counter.claim_index(authority, 254).unwrap(), 1);
+        assert_eq!(counter.authority, authority);
+        assert_eq!(counter.bump, 254);
+        assert_eq!(counter.next_index, 2);
+
+        counter.next_index = u64::MAX;
+        assert!(counter.claim_index(authority, 254).is_err());
+    }
+
+    #[test]
+    fn test_session_id_is_optional_and_bounded() {
+        assert!(validate_session_id("").is_ok());
+        assert!(validate_session_id("game123456").is_ok());
+        assert!(validate_session_id("game1234567").is_err());
+    }
+}
+
+#[cfg(test)]
+mod legacy_session_tests {
+    use crate::state::*;
+    use anchor_lang::prelude::*;
+    use anchor_lang::Discriminator;
+
+    fn legacy_session() -> LegacyGameSession {
+        LegacyGameSession {
+            session_id: "game123".to_string(),
+            authority: Pubkey::new_unique(),
+            session_bet: 1000,
+            game_mode: GameMode::WinnerTakesAllThreeVsThree,
+            team_a: LegacyTeam {
+                players: [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
+                ..Default::default()
+            },
+            team_b: LegacyTeam {
+                players: [Pubkey::new_unique(), Pubkey::default(), Pubkey::default(), Pubkey::default(), Pubkey::default()],
+                ..Default::default()
+            },
+            status: GameStatus::WaitingForPlayers,
+            created_at: 0,
+            bump: 255,
+            vault_bump: 254,
+            vault_token_bump: 0,
+        }
+    }
+
+    fn account_data(discriminator: &[u8], session: &LegacyGameSession) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend(session.try_to_vec().unwrap());
+        // Legacy accounts were allocated with room to spare
+        data.resize(data.len() + 64, 0);
+        data
+    }
+
+    #[test]
+    fn test_legacy_session_is_read_with_its_old_layout() {
+        let session = legacy_session();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = account_data(&GameSession::DISCRIMINATOR, &session);
+        let account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &crate::ID, false, 0);
+
+        let legacy = LegacyGameSession::try_from_account(&account).unwrap();
+        assert_eq!(legacy.session_id, session.session_id);
+        assert_eq!(legacy.authority, session.authority);
+        assert_eq!(legacy.vault_bump, 254);
+        assert_eq!(
+            legacy.joined_players(),
+            vec![session.team_a.players[0], session.team_a.players[1], session.team_b.players[0]]
+        );
+    }
+
+    #[test]
+    fn test_other_accounts_are_not_legacy_sessions() {
+        let session = legacy_session();
+        let key = Pubkey::new_unique();
+
+        let mut lamports = 0;
+        let mut data = account_data(&[0; 8], &session);
+        let account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &crate::ID, false, 0);
+        assert!(LegacyGameSession::try_from_account(&account).is_err());
+
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = account_data(&GameSession::DISCRIMINATOR, &session);
+        let account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+        assert!(LegacyGameSession::try_from_account(&account).is_err());
+    }
+}
+
+#[cfg(test)]
+mod session_authority_tests {
+    use crate::state::*;
+    use crate::test_utils::test_session;
+    use anchor_lang::prelude::*;
+
+    fn session() -> GameSession {
+        test_session()
+    }
+
+    #[test]
+    fn test_delegates_can_operate_until_removed() {
+        let mut game_session = session();
+        let operator = Pubkey::new_unique();
+        assert!(game_session.is_operator(game_session.authority));
+        assert!(!game_session.is_operator(operator));
+        assert!(!game_session.is_operator(Pubkey::default()));
+
+        game_session.add_delegate(operator).unwrap();
+        assert!(game_session.is_operator(operator));
+        assert!(game_session.add_delegate(operator).is_err());
+
+        game_session.remove_delegate(operator).unwrap();
+        assert!(!game_session.is_operator(operator));
+        assert!(game_session.remove_delegate(operator).is_err());
+        assert!(game_session.remove_delegate(Pubkey::default()).is_err());
+    }
+
+    #[test]
+    fn test_delegate_list_is_bounded() {
+        let mut game_session = session();
+        for _ in 0..MAX_SESSION_DELEGATES {
+            game_session.add_delegate(Pubkey::new_unique()).unwrap();
+        }
+        assert!(game_session.add_delegate(Pubkey::new_unique()).is_err());
+        assert!(game_session.add_delegate(Pubkey::default()).is_err());
+    }
+
+    #[test]
+    fn test_transfer_hands_over_and_drops_delegates() {
+        let mut game_session = session();
+        let previous = game_session.authority;
+        let operator = Pubkey::new_unique();
+        game_session.add_delegate(operator).unwrap();
+
+        let next = Pubkey::new_unique();
+        game_session.transfer_authority(next).unwrap();
+        assert_eq!(game_session.authority, next);
+        assert!(!game_session.is_operator(previous));
+        assert!(!game_session.is_operator(operator));
+        assert!(game_session.transfer_authority(Pubkey::default()).is_err());
+    }
+}
+
+#[cfg(test)]
+mod global_config_tests {
+    use crate::state::*;
+    use anchor_lang::prelude::*;
+
+    fn config() -> GlobalConfig {
+        GlobalConfig {
+            admin: Pubkey::new_unique(),
+            guardian: Pubkey::new_unique(),
+            paused: false,
+            bet_limits: BetLimits {
+                min_bet: 1,
+                max_bet: u64::MAX,
+            },
+            treasury: None,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_guardian_can_pause_but_not_resume() {
+        let mut global_config = config();
+        let guardian = global_config.guardian;
+
+        global_config.set_paused(guardian, true).unwrap();
+        assert!(global_config.paused);
+        assert!(global_config.set_paused(guardian, false).is_err());
+        assert!(global_config.paused);
+
+        let admin = global_config.admin;
+        global_config.set_paused(admin, false).unwrap();
+        assert!(!global_config.paused);
+    }
+
+    #[test]
+    fn test_other_keys_cannot_pause() {
+        let mut global_config = config();
+        assert!(global_config.set_paused(Pubkey::new_unique(), true).is_err());
+        assert!(!global_config.paused);
+    }
+
+    #[test]
+    fn test_sweep_requires_a_treasury() {
+        let mut global_config = config();
+        let authority_account = Pubkey::new_unique();
+
+        // Without a treasury nothing can be swept
+        assert!(!global_config.is_sweep_destination(authority_account));
+
+        // With one, only the treasury account is accepted
+        let treasury = Pubkey::new_unique();
+        global_config.treasury = Some(treasury);
+        assert!(global_config.is_sweep_destination(treasury));
+        assert!(!global_config.is_sweep_destination(authority_account));
+    }
+}
+
+#[cfg(test)]
+mod sweep_tests {
+    use crate::errors::WagerError;
+    use crate::state::*;
+    use crate::test_utils::test_session;
+    use anchor_lang::prelude::*;
+
+    /// Lobby of `mode` with `players` joined per team, every stake held
+    fn lobby(game_mode: GameMode, players: usize) -> GameSession {
+        let mut game_session = GameSession {
+            game_mode,
+            status: GameStatus::WaitingForPlayers,
+            held_deposits: 2 * players as u64 * 1000,
+            ..test_session()
+        };
+        for slot in 0..players {
+            game_session.team_a.players[slot] = Pubkey::new_unique();
+            game_session.team_a.player_spawns[slot] = INITIAL_SPAWNS;
+            game_session.team_b.players[slot] = Pubkey::new_unique();
+            game_session.team_b.player_spawns[slot] = INITIAL_SPAWNS;
+        }
+        game_session
+    }
+
+    #[test]
+    fn test_short_started_session_can_be_swept_after_settlement() {
+        let mut game_session = lobby(GameMode::WinnerTakesAllFiveVsFive, 5);
+        let no_show = game_session.team_a.players[2];
+
+        let refunds = game_session.start_short(4, &[no_show]).unwrap();
+        game_session
+            .release_deposits(refunds.iter().map(|(_, amount)| amount).sum())
+            .unwrap();
+        assert_eq!(
+            game_session.check_can_sweep().unwrap_err(),
+            WagerError::InvalidGameState.into()
+        );
+
+        let paid: u64 = game_session
+            .winner_payouts(0)
+            .unwrap()
+            .iter()
+            .map(|(_, payout)| payout)
+            .sum();
+        assert_eq!(paid, game_session.held_deposits);
+        game_session.complete_settlement();
+        assert!(game_session.check_can_sweep().is_ok());
+    }
+
+    #[test]
+    fn test_forfeit_affected_session_can_be_swept_after_settlement() {
+        let mut game_session = lobby(GameMode::WinnerTakesAllThreeVsThree, 3);
+        game_session.status = GameStatus::InProgress;
+        let team_b = game_session.team_b.players;
+        game_session.forfeit_player(0, game_session.team_a.players[1], false).unwrap();
+        for player in &team_b[..3] {
+            game_session.forfeit_player(1, *player, false).unwrap();
+        }
+
+        let paid: u64 = game_session
+            .winner_payouts(0)
+            .unwrap()
+            .iter()
+            .map(|(_, payout)| payout)
+            .sum();
+        assert_eq!(paid, game_session.held_deposits);
+        game_session.complete_settlement();
+        assert!(game_session.check_can_sweep().is_ok());
+    }
+
+    #[test]
+    fn test_unsettled_deposits_block_the_sweep() {
+        let mut game_session = lobby(GameMode::WinnerTakesAllOneVsOne, 1);
+        game_session.status = GameStatus::Completed;
+        assert_eq!(
+            game_session.check_can_sweep().unwrap_err(),
+            WagerError::UnsettledDeposits.into()
+        );
+
+        // Refunds release every deposit
+        game_session.status = GameStatus::WaitingForPlayers;
+        let refunds = game_session.refunds().unwrap();
+        game_session
+            .release_deposits(refunds.iter().map(|(_, amount)| amount).sum())
+            .unwrap();
+        game_session.status = GameStatus::Completed;
+        assert!(game_session.check_can_sweep().is_ok());
+    }
+}
+
+#[cfg(test)]
+mod freeze_tests {
+    use crate::state::*;
+    use crate::test_utils::test_session;
+
+    fn session() -> GameSession {
+        GameSession {
+            game_mode: GameMode::PayToSpawnOneVsOne,
+            ..test_session()
+        }
+    }
+
+    #[test]
+    fn test_settled_sessions_cannot_be_frozen() {
+        let mut game_session = session();
+        game_session.set_frozen(true).unwrap();
+        assert!(game_session.frozen);
+        game_session.set_frozen(false).unwrap();
+        assert!(!game_session.frozen);
+
+        game_session.status = GameStatus::Completed;
+        assert!(game_session.set_frozen(true).is_err());
+        assert!(!game_session.frozen);
+    }
+
+    #[test]
+    fn test_deposit_includes_spawn_purchases() {
+        let mut game_session = session();
+        game_session.spawn_config.price_increment = 100;
+        assert_eq!(game_session.player_deposit(0, 0).unwrap(), 1000);
+
+        game_session.team_a.spawn_purchases[0] = 3;
+        // Purchases cost 1000, 1100 and 1200
+        assert_eq!(game_session.player_deposit(0, 0).unwrap(), 4300);
+        assert_eq!(game_session.player_deposit(1, 0).unwrap(), 1000);
+        assert!(game_session.player_deposit(2, 0).is_err());
+    }
+}
+
+#[cfg(test)]
+mod start_session_tests {
+    use crate::errors::WagerError;
+    use crate::payout::vault_residual;
+    use crate::state::*;
+    use crate::test_utils::test_session;
+    use anchor_lang::prelude::*;
+
+    /// 5v5 lobby stuck at 5 players in team A and `team_b` in team B
+    fn lobby(team_b: usize) -> GameSession {
+        let mut game_session = GameSession {
+            game_mode: GameMode::WinnerTakesAllFiveVsFive,
+            status: GameStatus::WaitingForPlayers,
+            ..test_session()
+        };
+        for slot in 0..5 {
+            game_session.team_a.players[slot] = Pubkey::new_unique();
+            game_session.team_a.player_spawns[slot] = INITIAL_SPAWNS;
+        }
+        for slot in 0..team_b {
+            game_session.team_b.players[slot] = Pubkey::new_unique();
+            game_session.team_b.player_spawns[slot] = INITIAL_SPAWNS;
+        }
+        game_session
+    }
+
+    #[test]
+    fn test_uneven_extra_is_refunded() {
+        let mut game_session = lobby(4);
+        let last_joined = game_session.team_a.players[4];
+
+        let refunds = game_session.start_short(4, &[]).unwrap();
+        assert_eq!(refunds, vec![(last_joined, 1000)]);
+        assert!(game_session.status == GameStatus::InProgress);
+        assert_eq!(game_session.team_a.player_count(), 4);
+        assert_eq!(game_session.team_b.player_count(), 4);
+        assert!(!game_session.contains_player(last_joined));
+        assert_eq!(game_session.team_a.player_spawns[4], 0);
+        assert_eq!(game_session.team_players(0).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_no_shows_forfeit_their_stake() {
+        let mut game_session = lobby(5);
+        let no_show = game_session.team_b.players[1];
+        let last_joined = game_session.team_a.players[4];
+
+        // The no-show is dropped without a refund, team A is trimmed to match
+        let refunds = game_session.start_short(3, &[no_show]).unwrap();
+        assert_eq!(refunds, vec![(last_joined, 1000)]);
+        assert!(!game_session.contains_player(no_show));
+        assert_eq!(game_session.team_players(1).unwrap().len(), 4);
+        assert_eq!(game_session.team_players(0).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_no_show_stake_goes_to_the_winners() {
+        let mut game_session = GameSession {
+            held_deposits: 10 * 1000,
+            fee_bps: 500,
+            ..lobby(5)
+        };
+        let no_show = game_session.team_b.players[1];
+
+        let refunds = game_session.start_short(3, &[no_show]).unwrap();
+        game_session
+            .release_deposits(refunds.iter().map(|(_, amount)| amount).sum())
+            .unwrap();
+        assert_eq!(game_session.held_deposits, 9 * 1000);
+
+        // The four winners share all nine stakes left in the vault
+        let payouts = game_session.winner_payouts(1).unwrap();
+        assert_eq!(payouts.len(), 4);
+        let share = 9 * 1000 / 4;
+        assert!(payouts
+            .iter()
+            .all(|(_, payout)| *payout == share - game_session.fee_on(share)));
+        let amounts: Vec<u64> = payouts.iter().map(|(_, payout)| *payout).collect();
+        assert_eq!(
+            vault_residual(9 * 1000, &amounts).unwrap(),
+            4 * game_session.fee_on(share)
+        );
+
+        // Nothing is held once settled, so the vault can be swept
+        game_session.complete_settlement();
+        assert_eq!(game_session.held_deposits, 0);
+        assert!(game_session.status == GameStatus::Completed);
+    }
+
+    #[test]
+    fn test_start_requires_minimum_per_team() {
+        let mut game_session = lobby(3);
+        assert_eq!(
+            game_session.start_short(4, &[]).unwrap_err(),
+            WagerError::NotEnoughPlayers.into()
+        );
+        assert_eq!(
+            game_session.start_short(0, &[]).unwrap_err(),
+            WagerError::InvalidPlayerCount.into()
+        );
+        assert_eq!(
+            game_session.start_short(6, &[]).unwrap_err(),
+            WagerError::InvalidPlayerCount.into()
+        );
+        assert!(game_session.status == GameStatus::WaitingForPlayers);
+
+        // Unknown no-shows are rejected
+        assert_eq!(
+            game_session
+                .start_short(1, &[Pubkey::new_unique()])
+                .unwrap_err(),
+            WagerError::PlayerNotFound.into()
+        );
+
+        let refunds = game_session.start_short(3, &[]).unwrap();
+        assert_eq!(refunds.len(), 2);
+        assert_eq!(
+            game_session.start_short(3, &[]).unwrap_err(),
+            WagerError::InvalidGameState.into()
+        );
+    }
+}
+
+#[cfg(test)]
+mod forfeit_tests {
+    use crate::errors::WagerError;
+    use crate::payout::{split_pot, vault_residual};
+    use crate::state::*;
+    use crate::test_utils::test_session;
+    use anchor_lang::prelude::*;
+
+    /// 3v3 match in progress
+    fn match_in_progress(game_mode: GameMode) -> GameSession {
+        let mut game_session = GameSession {
+            session_bet: 1000,
+            game_mode,
+            ..test_session()
+        };
+        for slot in 0..3 {
+            game_session.team_a.players[slot] = Pubkey::new_unique();
+            game_session.team_a.player_spawns[slot] = INITIAL_SPAWNS;
+            game_session.team_b.players[slot] = Pubkey::new_unique();
+            game_session.team_b.player_spawns[slot] = INITIAL_SPAWNS;
+        }
+        game_session
+    }
+
+    #[test]
+    fn test_forfeited_player_is_excluded_from_winnings() {
+        let mut game_session = match_in_progress(GameMode::WinnerTakesAllThreeVsThree);
+        let quitter = game_session.team_a.players[1];
+
+        assert!(!game_session.forfeit_player(0, quitter, false).unwrap());
+        assert!(game_session.team_a.player_forfeited[1]);
+        assert!(game_session.is_playing());
+        assert!(!game_session.team_players(0).unwrap().contains(&quitter));
+        assert_eq!(game_session.team_players(0).unwrap().len(), 2);
+        assert_eq!(game_session.get_active_players().len(), 5);
+        // Still on the scoreboard
+        assert_eq!(game_session.get_joined_players().len(), 6);
+
+        assert_eq!(
+            game_session.forfeit_player(0, quitter, false).unwrap_err(),
+            WagerError::PlayerForfeited.into()
+        );
+        assert_eq!(
+            game_session
+                .forfeit_player(1, Pubkey::new_unique(), false)
+                .unwrap_err(),
+            WagerError::PlayerNotFound.into()
+        );
+    }
+
+    #[test]
+    fn test_whole_team_forfeit_ends_the_match() {
+        let mut game_session = match_in_progress(GameMode::WinnerTakesAllThreeVsThree);
+        let team_b = game_session.team_b.players;
+
+        game_session.forfeit_player(1, team_b[0], false).unwrap();
+        game_session.forfeit_player(1, team_b[1], false).unwrap();
+        // The last player of the team hands the match over even without end_match
+        assert!(game_session.forfeit_player(1, team_b[2], false).unwrap());
+        assert!(!game_session.is_playing());
+        assert_eq!(game_session.forfeit_winner, Some(0));
+        assert!(game_session.check_can_settle_for(0).is_ok());
+        assert_eq!(
+            game_session.check_can_settle_for(1).unwrap_err(),
+            WagerError::NoActivePlayers.into()
+        );
+
+        // No more kills once the match ended
+        let team_a = game_session.team_a.players;
+        assert_eq!(
+            game_session.add_kill(0, team_a[0], 1, team_b[0]).unwrap_err(),
+            WagerError::GameNotInProgress.into()
+        );
+        assert_eq!(
+            game_session.forfeit_player(0, team_a[0], true).unwrap_err(),
+            WagerError::GameNotInProgress.into()
+        );
+    }
+
+    #[test]
+    fn test_end_match_concedes_before_the_team_is_empty() {
+        let mut game_session = match_in_progress(GameMode::WinnerTakesAllThreeVsThree);
+        let quitter = game_session.team_a.players[0];
+
+        assert!(game_session.forfeit_player(0, quitter, true).unwrap());
+        assert!(!game_session.is_playing());
+        assert_eq!(game_session.forfeit_winner, Some(1));
+        assert!(game_session.check_can_settle_for(1).is_ok());
+        assert_eq!(
+            game_session.check_can_settle_for(0).unwrap_err(),
+            WagerError::MatchForfeitedToOtherTeam.into()
+        );
+    }
+
+    #[test]
+    fn test_team_without_active_players_cannot_win() {
+        let mut game_session = match_in_progress(GameMode::WinnerTakesAllThreeVsThree);
+        assert!(game_session.check_can_settle_for(1).is_ok());
+
+        // Forfeits marked without going through forfeit_player leave no winner recorded
+        game_session.team_b.player_forfeited = [true; 5];
+        assert_eq!(game_session.forfeit_winner, None);
+        assert_eq!(
+            game_session.check_can_settle_for(1).unwrap_err(),
+            WagerError::NoActivePlayers.into()
+        );
+        assert!(game_session.check_can_settle_for(0).is_ok());
+    }
+
+    #[test]
+    fn test_forfeited_winner_share_goes_to_the_teammates() {
+        let mut game_session = GameSession {
+            held_deposits: 6 * 1000,
+            ..match_in_progress(GameMode::WinnerTakesAllThreeVsThree)
+        };
+        let quitter = game_session.team_a.players[0];
+        game_session.forfeit_player(0, quitter, false).unwrap();
+
+        // The two remaining winners split all six stakes
+        let payouts = game_session.winner_payouts(0).unwrap();
+        assert_eq!(
+            payouts,
+            vec![
+                (game_session.team_a.players[1], 3000),
+                (game_session.team_a.players[2], 3000),
+            ]
+        );
+        let amounts: Vec<u64> = payouts.iter().map(|(_, payout)| *payout).collect();
+        assert_eq!(vault_residual(6 * 1000, &amounts).unwrap(), 0);
+
+        game_session.complete_settlement();
+        assert_eq!(game_session.held_deposits, 0);
+    }
+
+    #[test]
+    fn test_forfeited_player_shares_no_pay_to_spawn_pot() {
+        let mut game_session = match_in_progress(GameMode::PayToSpawnThreeVsThree);
+        let quitter = game_session.team_a.players[0];
+        game_session.team_a.player_kills[0] = 10;
+        game_session.team_b.player_kills[0] = 5;
+
+        game_session.forfeit_player(0, quitter, false).unwrap();
+        assert_eq!(
+            game_session.add_spawns(0, 0).unwrap_err(),
+            WagerError::PlayerForfeited.into()
+        );
+
+        let scores = game_session
+            .get_active_players()
+            .iter()
+            .map(|(team, slot, _)| game_session.get_player_score(*team, *slot).unwrap())
+            .collect::<Vec<u64>>();
+        assert_eq!(scores.len(), 5);
+        let (payouts, remainder) = split_pot(6000, &scores);
+        assert_eq!(payouts.iter().sum::<u64>() + remainder, 6000);
+        assert_eq!(payouts[2], 6000);
+    }
+
+    #[test]
+    fn test_rejoined_slot_clears_forfeit() {
+        let mut team = Team::default();
+        team.players[0] = Pubkey::new_unique();
+        assert!(!team.has_forfeited());
+        team.player_forfeited[0] = true;
+        assert!(team.has_forfeited());
+        team.clear_slot(0);
+        assert!(!team.player_forfeited[0]);
+        assert!(!Team::default().has_forfeited());
     }
 }
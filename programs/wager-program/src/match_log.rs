@@ -0,0 +1,69 @@
+//! Merkle commitments to the off-chain match event log
+//!
+//! Leaves and inner nodes are hashed with distinct prefixes so a leaf can never
+//! be passed off as an inner node. Pairs are hashed in sorted order, so a proof
+//! is just the list of sibling hashes from the leaf up to the root.
+use anchor_lang::solana_program::hash::hashv;
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Hash of a serialized match event
+pub fn leaf_hash(event: &[u8]) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, event]).to_bytes()
+}
+
+/// Hash of two sibling nodes, independent of their order
+pub fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Root of the tree over `leaves`, an odd node is carried up unchanged.
+/// An empty log has an all-zero root.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0; 32];
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level[0]
+}
+
+/// Sibling hashes proving the leaf at `index` is part of `leaves`
+pub fn merkle_proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            proof.push(level[sibling]);
+        }
+        level = parent_level(&level);
+        index /= 2;
+    }
+    proof
+}
+
+/// Hashes each pair of nodes into the level above
+fn parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Checks that `leaf` is part of the tree committed to by `root`
+pub fn verify_proof(root: &[u8; 32], leaf: &[u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(*leaf, |node, sibling| node_hash(&node, sibling));
+    computed == *root
+}
@@ -0,0 +1,40 @@
+//! Proportional split of a pay-to-spawn pot and vault solvency checks
+use crate::errors::WagerError;
+use anchor_lang::prelude::*;
+
+/// Splits `pot` in proportion to `scores`, rounding every share down. When
+/// every score is zero the pot is split evenly instead. Returns the shares and
+/// the rounding remainder, which always add up to exactly `pot`.
+pub fn split_pot(pot: u64, scores: &[u64]) -> (Vec<u64>, u64) {
+    let total_score: u128 = scores.iter().map(|score| *score as u128).sum();
+    let weights: Vec<u128> = if total_score == 0 {
+        vec![1; scores.len()]
+    } else {
+        scores.iter().map(|score| *score as u128).collect()
+    };
+    let total_weight: u128 = weights.iter().sum();
+    if total_weight == 0 {
+        return (Vec::new(), pot);
+    }
+
+    // Each share is at most `pot`, so it always fits back into a u64
+    let shares: Vec<u64> = weights
+        .iter()
+        .map(|weight| (pot as u128 * weight / total_weight) as u64)
+        .collect();
+    let paid: u64 = shares.iter().sum();
+    (shares, pot - paid)
+}
+
+/// Balance a vault is left with after paying out `amounts`. Fails before any
+/// transfer when the vault cannot cover all of them, so settlements never stop
+/// halfway through.
+pub fn vault_residual(vault_balance: u64, amounts: &[u64]) -> Result<u64> {
+    let outgoing = amounts
+        .iter()
+        .try_fold(0u64, |total, amount| total.checked_add(*amount))
+        .ok_or(error!(WagerError::ArithmeticError))?;
+    vault_balance
+        .checked_sub(outgoing)
+        .ok_or(error!(WagerError::InsufficientVaultBalance))
+}
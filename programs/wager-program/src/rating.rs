@@ -0,0 +1,58 @@
+//! Integer Elo rating math used on settlement
+
+/// Rating given to a freshly created player profile
+pub const DEFAULT_RATING: u32 = 1200;
+
+/// Maximum rating change of a single game
+pub const RATING_K_FACTOR: i64 = 32;
+
+/// Scale of expected and actual scores (10_000 = certain win)
+const SCORE_SCALE: i64 = 10_000;
+
+/// Rating difference covered by each entry of the expected score table
+const RATING_STEP: u64 = 25;
+
+/// Expected score against an opponent rated `i * RATING_STEP` points higher,
+/// in basis points. Differences beyond the table are clamped to its last entry.
+const EXPECTED_SCORE_BPS: [i64; 33] = [
+    5000, 4641, 4285, 3937, 3599, 3275, 2966, 2675, 2403, 2150, 1917, 1704, 1510, 1334, 1177, 1035,
+    909, 797, 698, 610, 532, 464, 405, 352, 307, 267, 232, 201, 175, 152, 132, 114, 99,
+];
+
+/// Expected score of `rating` against `opponent_rating` in basis points,
+/// linearly interpolated between table entries
+pub fn expected_score_bps(rating: u32, opponent_rating: u32) -> i64 {
+    let difference = opponent_rating as i64 - rating as i64;
+    let distance = difference.unsigned_abs();
+    let step = (distance / RATING_STEP) as usize;
+
+    let expected = if step + 1 >= EXPECTED_SCORE_BPS.len() {
+        EXPECTED_SCORE_BPS[EXPECTED_SCORE_BPS.len() - 1]
+    } else {
+        let lower = EXPECTED_SCORE_BPS[step];
+        let upper = EXPECTED_SCORE_BPS[step + 1];
+        let offset = (distance % RATING_STEP) as i64;
+        lower - (lower - upper) * offset / RATING_STEP as i64
+    };
+
+    if difference >= 0 {
+        expected
+    } else {
+        SCORE_SCALE - expected
+    }
+}
+
+/// Rating change of a side rated `rating` after playing `opponent_rating`
+pub fn rating_change(rating: u32, opponent_rating: u32, won: bool) -> i64 {
+    let actual = if won { SCORE_SCALE } else { 0 };
+    RATING_K_FACTOR * (actual - expected_score_bps(rating, opponent_rating)) / SCORE_SCALE
+}
+
+/// Average rating of a team, an empty team counts as a default rated one
+pub fn team_average_rating(ratings: &[u32]) -> u32 {
+    if ratings.is_empty() {
+        return DEFAULT_RATING;
+    }
+    let total: u64 = ratings.iter().map(|rating| *rating as u64).sum();
+    (total / ratings.len() as u64) as u32
+}
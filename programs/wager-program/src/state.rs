@@ -1,6 +1,9 @@
 //! State accounts for the betting program
 use crate::errors::WagerError;
+use crate::match_log::{leaf_hash, verify_proof};
+use crate::rating::{rating_change, DEFAULT_RATING};
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 
 /// Game mode defining the team sizes
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
@@ -25,29 +28,181 @@ impl GameMode {
             Self::PayToSpawnFiveVsFive => 5,
         }
     }
+
+    /// Whether players buy spawns and share the pot by score
+    pub fn is_pay_to_spawn(&self) -> bool {
+        matches!(
+            self,
+            Self::PayToSpawnOneVsOne | Self::PayToSpawnThreeVsThree | Self::PayToSpawnFiveVsFive
+        )
+    }
 }
 
 /// Status of a game session
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Default)]
 pub enum GameStatus {
+    #[default]
     WaitingForPlayers, // Waiting for players to join
-    InProgress,        // Game is active with all players joined
-    Completed,         // Game has finished and rewards distributed
+    InProgress, // Game is active with all players joined
+    Completed,  // Game has finished and rewards distributed
+}
+
+/// Maximum number of kills accepted by a single batch
+pub const MAX_KILLS_PER_BATCH: usize = 64;
+
+/// What happened in a kill event
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum KillKind {
+    Normal,        // Killer takes down an opponent
+    Assist,        // Killer helped take down an opponent, the victim does not die
+    Suicide,       // Victim killed themselves, killer is the victim
+    TeamKill,      // Killer takes down a teammate
+    Environmental, // Victim died without a killer, killer fields are ignored
+}
+
+/// Compact kill record addressing players by team and slot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct KillEvent {
+    pub kind: KillKind,   // What happened
+    pub killer_team: u8,  // Team of the killer
+    pub killer_index: u8, // Slot of the killer in its team
+    pub victim_team: u8,  // Team of the victim
+    pub victim_index: u8, // Slot of the victim in its team
+}
+
+/// Range of player ratings allowed to join a session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct RatingBand {
+    pub min_rating: u32, // Lowest rating allowed to join
+    pub max_rating: u32, // Highest rating allowed to join
+}
+
+impl RatingBand {
+    pub fn contains(&self, rating: u32) -> bool {
+        self.min_rating <= rating && rating <= self.max_rating
+    }
+}
+
+/// Spawns a player starts with when joining a session
+pub const INITIAL_SPAWNS: u16 = 10;
+
+/// Spawns granted by a purchase when the session does not configure it
+pub const DEFAULT_SPAWNS_PER_PURCHASE: u16 = 10;
+
+/// Spawn purchases allowed per player when the session does not configure it
+pub const DEFAULT_MAX_SPAWN_PURCHASES: u16 = 10;
+
+/// Pricing and limits of spawn purchases in pay-to-spawn sessions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct SpawnConfig {
+    pub spawns_per_purchase: u16, // Spawns granted by each purchase
+    pub price_per_purchase: u64,  // Price of a player's first purchase
+    pub price_increment: u64,     // Added to the price for each earlier purchase of the player
+    pub max_purchases: u16,       // Maximum number of purchases per player
+}
+
+impl SpawnConfig {
+    /// Default pricing: every purchase costs the session bet and grants 10 spawns
+    pub fn from_session_bet(session_bet: u64) -> Self {
+        Self {
+            spawns_per_purchase: DEFAULT_SPAWNS_PER_PURCHASE,
+            price_per_purchase: session_bet,
+            price_increment: 0,
+            max_purchases: DEFAULT_MAX_SPAWN_PURCHASES,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.spawns_per_purchase > 0 && self.price_per_purchase > 0,
+            WagerError::InvalidSpawnConfig
+        );
+        Ok(())
+    }
+
+    /// Price of the next purchase of a player who already made `purchases_made`
+    pub fn purchase_price(&self, purchases_made: u16) -> Result<u64> {
+        require!(
+            purchases_made < self.max_purchases,
+            WagerError::SpawnPurchaseLimitReached
+        );
+        self.price_increment
+            .checked_mul(purchases_made as u64)
+            .and_then(|increment| increment.checked_add(self.price_per_purchase))
+            .ok_or(error!(WagerError::ArithmeticError))
+    }
+
+    /// Combined price of a player's first `purchases` purchases
+    pub fn total_price(&self, purchases: u16) -> Result<u64> {
+        (0..purchases as u64).try_fold(0u64, |total, purchase| {
+            self.price_increment
+                .checked_mul(purchase)
+                .and_then(|increment| increment.checked_add(self.price_per_purchase))
+                .and_then(|price| total.checked_add(price))
+                .ok_or(error!(WagerError::ArithmeticError))
+        })
+    }
 }
 
-impl Default for GameStatus {
+/// Score earned per kill when the session does not configure it
+pub const DEFAULT_KILL_WEIGHT: u16 = 2;
+
+/// Score lost per death when the session does not configure it
+pub const DEFAULT_DEATH_PENALTY: u16 = 1;
+
+/// Score earned per assist when the session does not configure it
+pub const DEFAULT_ASSIST_WEIGHT: u16 = 1;
+
+/// How a pay-to-spawn pot is shared between the players
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct PayoutConfig {
+    pub kill_weight: u16,                      // Score earned per kill
+    pub death_penalty: u16,                    // Score lost per death
+    pub assist_weight: u16,                    // Score earned per assist
+    pub team_kill_penalty: u16,                // Score lost per team kill, 0 to not penalize
+    pub remainder_destination: Option<Pubkey>, // Token account for the rounding remainder, None for the top scorer
+}
+
+impl Default for PayoutConfig {
     fn default() -> Self {
-        Self::WaitingForPlayers
+        Self {
+            kill_weight: DEFAULT_KILL_WEIGHT,
+            death_penalty: DEFAULT_DEATH_PENALTY,
+            assist_weight: DEFAULT_ASSIST_WEIGHT,
+            team_kill_penalty: 0,
+            remainder_destination: None,
+        }
+    }
+}
+
+impl PayoutConfig {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.kill_weight > 0, WagerError::InvalidPayoutConfig);
+        Ok(())
+    }
+
+    /// Share of the pot earned by the player in a slot, never below zero
+    pub fn score(&self, team: &Team, player_index: usize) -> u64 {
+        let earned = team.player_kills[player_index] as u64 * self.kill_weight as u64
+            + team.player_assists[player_index] as u64 * self.assist_weight as u64;
+        let lost = team.player_deaths[player_index] as u64 * self.death_penalty as u64
+            + team.player_team_kills[player_index] as u64 * self.team_kill_penalty as u64;
+        earned.saturating_sub(lost)
     }
 }
 
 /// Represents a team in the game
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
 pub struct Team {
-    pub players: [Pubkey; 5],    // Array of player public keys
-    pub total_bet: u64,          // Total amount bet by team (in lamports)
-    pub player_spawns: [u16; 5], // Number of spawns remaining for each player
-    pub player_kills: [u16; 5],  // Number of kills for each player
+    pub players: [Pubkey; 5],        // Array of player public keys
+    pub total_bet: u64,              // Total amount bet by team (in lamports)
+    pub player_spawns: [u16; 5],     // Number of spawns remaining for each player
+    pub player_kills: [u16; 5],      // Number of kills for each player
+    pub player_deaths: [u16; 5],     // Number of deaths for each player
+    pub spawn_purchases: [u16; 5],   // Number of spawn purchases for each player
+    pub player_assists: [u16; 5],    // Number of assists for each player
+    pub player_team_kills: [u16; 5], // Number of teammates killed by each player
+    pub player_forfeited: [bool; 5], // Whether each player forfeited the match
 }
 
 impl Team {
@@ -60,13 +215,329 @@ impl Team {
             .map(|(i, _)| i)
             .ok_or_else(|| error!(WagerError::TeamIsFull))
     }
+
+    /// Number of occupied slots
+    pub fn player_count(&self) -> usize {
+        self.players
+            .iter()
+            .filter(|player| **player != Pubkey::default())
+            .count()
+    }
+
+    /// Frees a slot along with the stats of its player
+    pub fn clear_slot(&mut self, index: usize) {
+        self.players[index] = Pubkey::default();
+        self.player_spawns[index] = 0;
+        self.player_kills[index] = 0;
+        self.player_deaths[index] = 0;
+        self.spawn_purchases[index] = 0;
+        self.player_assists[index] = 0;
+        self.player_team_kills[index] = 0;
+        self.player_forfeited[index] = false;
+    }
+
+    /// Whether every player of the team forfeited, false for an empty team
+    pub fn has_forfeited(&self) -> bool {
+        let mut joined = self
+            .players
+            .iter()
+            .zip(self.player_forfeited)
+            .filter(|(player, _)| **player != Pubkey::default())
+            .peekable();
+        joined.peek().is_some() && joined.all(|(_, forfeited)| forfeited)
+    }
+}
+
+/// Latest commitment of the game server to the off-chain match event log
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Default)]
+pub struct MatchLog {
+    pub root: [u8; 32],   // Merkle root of the events logged so far
+    pub event_count: u64, // Number of events covered by the root
+    pub commits: u32,     // Number of roots committed
+    pub finalized: bool,  // Whether the root covers the whole match
+}
+
+/// Outcome side bets of a session are settled against
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Default)]
+pub enum SidePoolOutcome {
+    #[default]
+    Open, // Match not settled yet
+    TeamWon(u8), // Winning team of the match
+    Cancelled,   // Match was refunded, every side bet is refunded too
+}
+
+/// Parimutuel pool of spectator bets on the outcome of a session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Default)]
+pub struct SideBetPool {
+    pub team_totals: [u64; 2],    // Total staked on team A and team B
+    pub outcome: SidePoolOutcome, // Result the pool is settled against
+}
+
+impl SideBetPool {
+    /// Adds a stake on a team to the pool
+    pub fn add_bet(&mut self, team: u8, amount: u64) -> Result<()> {
+        let total = self
+            .team_totals
+            .get_mut(team as usize)
+            .ok_or(error!(WagerError::InvalidTeamSelection))?;
+        *total = total
+            .checked_add(amount)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        Ok(())
+    }
+
+    /// Amount owed to a side bet: winners share the whole pool in proportion to
+    /// their stake, and every bet is refunded when the match was cancelled or
+    /// nobody backed the winning team
+    pub fn payout(&self, team: u8, amount: u64) -> Result<u64> {
+        let winning_team = match self.outcome {
+            SidePoolOutcome::Open => return err!(WagerError::SideBetsNotSettled),
+            SidePoolOutcome::Cancelled => return Ok(amount),
+            SidePoolOutcome::TeamWon(winning_team) => winning_team as usize,
+        };
+
+        let winning_total = self.team_totals[winning_team];
+        if winning_total == 0 {
+            return Ok(amount);
+        }
+        if team as usize != winning_team {
+            return Ok(0);
+        }
+
+        let pool = self.team_totals[0] as u128 + self.team_totals[1] as u128;
+        u64::try_from(amount as u128 * pool / winning_total as u128)
+            .map_err(|_| error!(WagerError::ArithmeticError))
+    }
+}
+
+/// Stake of a spectator on one team of a session
+#[account]
+pub struct SideBet {
+    pub game_session: Pubkey, // Game session the bet is on
+    pub bettor: Pubkey,       // Wallet that placed the bet
+    pub team: u8,             // Team backed by the bet
+    pub amount: u64,          // Total amount staked
+    pub bump: u8,             // PDA bump
+}
+
+/// Longest best-of-N series a session can play
+pub const MAX_SERIES_LENGTH: u8 = 9;
+
+/// Best-of-N series played by the roster of a winner takes all session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Default)]
+pub struct Series {
+    pub best_of: u8,         // Number of rounds of the series, odd
+    pub round_wins: [u8; 2], // Rounds won by team A and team B
+    pub rounds_played: u8,   // Number of rounds recorded
+    pub round_winners: [u8; MAX_SERIES_LENGTH as usize], // Winning team of every recorded round
+}
+
+impl Series {
+    pub fn new(best_of: u8) -> Result<Self> {
+        require!(
+            best_of % 2 == 1 && best_of <= MAX_SERIES_LENGTH,
+            WagerError::InvalidSeriesLength
+        );
+        Ok(Self {
+            best_of,
+            ..Default::default()
+        })
+    }
+
+    /// Rounds a team needs to clinch the series
+    pub fn wins_needed(&self) -> u8 {
+        self.best_of / 2 + 1
+    }
+
+    /// Team that clinched the series, if any
+    pub fn winner(&self) -> Option<u8> {
+        (0..2).find(|team| self.round_wins[*team as usize] >= self.wins_needed())
+    }
+
+    /// Records the winner of the next round
+    pub fn record_round(&mut self, winning_team: u8) -> Result<()> {
+        require!(
+            winning_team == 0 || winning_team == 1,
+            WagerError::InvalidWinningTeam
+        );
+        require!(self.winner().is_none(), WagerError::SeriesAlreadyDecided);
+
+        self.round_winners[self.rounds_played as usize] = winning_team;
+        self.rounds_played += 1;
+        self.round_wins[winning_team as usize] += 1;
+        Ok(())
+    }
+}
+
+/// Longest human-readable session id, sessions are addressed by authority and index
+pub const MAX_SESSION_ID_LEN: usize = 10;
+
+/// Number of sessions an authority has created, the next one gets `next_index`
+#[account]
+pub struct SessionCounter {
+    pub authority: Pubkey, // Game server the counter belongs to
+    pub next_index: u64,   // Index of the next session of the authority
+    pub bump: u8,          // PDA bump
+}
+
+impl SessionCounter {
+    /// Hands out the next session index, initializing a fresh counter first
+    pub fn claim_index(&mut self, authority: Pubkey, bump: u8) -> Result<u64> {
+        if self.authority == Pubkey::default() {
+            self.authority = authority;
+            self.bump = bump;
+        }
+        let index = self.next_index;
+        self.next_index = index
+            .checked_add(1)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        Ok(index)
+    }
+}
+
+/// Range of session bets accepted by the program or a lobby template
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub struct BetLimits {
+    pub min_bet: u64, // Smallest bet per player, never zero
+    pub max_bet: u64, // Largest bet per player
+}
+
+impl BetLimits {
+    /// Limits allowing a single bet amount
+    pub fn exactly(bet: u64) -> Self {
+        Self {
+            min_bet: bet,
+            max_bet: bet,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_bet > 0 && self.min_bet <= self.max_bet,
+            WagerError::InvalidBetLimits
+        );
+        Ok(())
+    }
+
+    /// Checks that a bet is inside the limits, a zero bet never is
+    pub fn check(&self, bet: u64) -> Result<()> {
+        require!(
+            bet > 0 && bet >= self.min_bet && bet <= self.max_bet,
+            WagerError::InvalidBetAmount
+        );
+        Ok(())
+    }
+}
+
+/// Program wide settings, a single account at a fixed address
+#[account]
+pub struct GlobalConfig {
+    pub admin: Pubkey,            // Key allowed to change the config and lift a pause
+    pub guardian: Pubkey,         // Key allowed to pause the program in an incident
+    pub paused: bool,             // Whether new sessions, joins, spawns and kills are halted
+    pub bet_limits: BetLimits,    // Bets allowed in any session
+    pub treasury: Option<Pubkey>, // Token account receiving swept vault leftovers, sweeping is disabled without one
+    pub bump: u8,                 // PDA bump
+}
+
+impl GlobalConfig {
+    /// Pauses or resumes the program. The guardian can only pause,
+    /// resuming is left to the admin.
+    pub fn set_paused(&mut self, signer: Pubkey, paused: bool) -> Result<()> {
+        let allowed = signer == self.admin || (paused && signer == self.guardian);
+        require!(allowed, WagerError::UnauthorizedConfig);
+        self.paused = paused;
+        Ok(())
+    }
+
+    /// Whether leftovers of a session vault may be swept to the token account
+    /// `destination`, only a configured treasury receives them
+    pub fn is_sweep_destination(&self, destination: Pubkey) -> bool {
+        self.treasury == Some(destination)
+    }
+}
+
+/// Adds one to a player counter, failing instead of wrapping around
+fn checked_increment(counter: u16) -> Result<u16> {
+    counter
+        .checked_add(1)
+        .ok_or(error!(WagerError::ArithmeticError))
+}
+
+/// Checks the length of a human-readable session id
+pub fn validate_session_id(session_id: &str) -> Result<()> {
+    require!(
+        session_id.len() <= MAX_SESSION_ID_LEN,
+        WagerError::InvalidSessionId
+    );
+    Ok(())
+}
+
+/// Most operators a session can delegate kill recording to
+pub const MAX_SESSION_DELEGATES: usize = 4;
+
+/// Basis points of a whole amount
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+/// Longest id prefix of a lobby template, leaving room for the session counter
+pub const MAX_TEMPLATE_ID_LEN: usize = 4;
+
+/// Highest session counter of a lobby template, six hex digits
+pub const MAX_TEMPLATE_SESSIONS: u64 = 0xff_ffff;
+
+/// Lobby settings shared by every session a game server creates from it
+#[account]
+pub struct LobbyTemplate {
+    pub template_id: String, // Unique prefix of the ids of sessions created from the template
+    pub authority: Pubkey,   // Game server allowed to create sessions from the template
+    pub game_mode: GameMode, // Game configuration of the sessions
+    pub session_bet: u64,    // Required bet amount per player
+    pub mint: Pubkey,        // Token the sessions are played for
+    pub fee_bps: u16,        // Share of the payouts kept as a fee, in basis points
+    pub join_timeout: i64,   // Seconds players have to join a session, 0 for no limit
+    pub next_session_index: u64, // Counter of the next session id
+    pub bet_limits: BetLimits, // Bets the sessions of the template may pick from
+    pub bump: u8,            // PDA bump
+}
+
+impl LobbyTemplate {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.template_id.is_empty() && self.template_id.len() <= MAX_TEMPLATE_ID_LEN,
+            WagerError::InvalidTemplate
+        );
+        require!(
+            self.fee_bps < BPS_DENOMINATOR && self.join_timeout >= 0,
+            WagerError::InvalidTemplate
+        );
+        self.bet_limits.validate()?;
+        self.bet_limits.check(self.session_bet)
+    }
+
+    /// Id of the next session. The counter has a fixed width, so ids of
+    /// different templates never collide.
+    pub fn next_session_id(&self) -> String {
+        format!("{}{:06x}", self.template_id, self.next_session_index)
+    }
+
+    /// Moves the counter past the session created with `next_session_id`
+    pub fn advance_session_index(&mut self) -> Result<()> {
+        require!(
+            self.next_session_index < MAX_TEMPLATE_SESSIONS,
+            WagerError::TemplateExhausted
+        );
+        self.next_session_index += 1;
+        Ok(())
+    }
 }
 
 /// Represents a game session between teams with its own pool
 #[account]
 pub struct GameSession {
-    pub session_id: String,  // Unique identifier for the game
-    pub authority: Pubkey,   // Creator of the game session
+    pub session_id: String,  // Optional human-readable id, empty when not set
+    pub session_index: u64,  // Index of the session among those of its creator
+    pub creator: Pubkey,     // Game server that created the session, part of its address
+    pub authority: Pubkey,   // Game server currently running the session
     pub session_bet: u64,    // Required bet amount per player
     pub game_mode: GameMode, // Game configuration (1v1, 2v2, 5v5)
     pub team_a: Team,        // First team
@@ -76,9 +547,97 @@ pub struct GameSession {
     pub bump: u8,            // PDA bump
     pub vault_bump: u8,      // Add this field for vault PDA bump
     pub vault_token_bump: u8,
+    pub rating_band: Option<RatingBand>, // Ratings allowed to join, None for unranked sessions
+    pub spawn_config: SpawnConfig,       // Spawn purchase pricing for pay-to-spawn modes
+    pub payout_config: PayoutConfig,     // Pot sharing for pay-to-spawn modes
+    pub match_log: Option<MatchLog>, // Committed match log, None when kills are only recorded on-chain
+    pub side_pool: SideBetPool,      // Spectator bets on the outcome of the match
+    pub series: Option<Series>,      // Best-of-N series, None for a single match
+    pub fee_bps: u16, // Share of the payouts kept in the vault as a fee, in basis points
+    pub join_deadline: i64, // Time after which players can no longer join, 0 for none
+    pub delegates: [Pubkey; MAX_SESSION_DELEGATES], // Extra game servers allowed to record kills
+    pub frozen: bool, // Set by the config admin to halt kills, spawns and settlement
+    pub forfeit_winner: Option<u8>, // Team left standing when the other one forfeited or conceded the match
+    pub held_deposits: u64,         // Player payments in the vault not refunded or paid out yet
 }
 
 impl GameSession {
+    /// Account size of a game session with a session id of up to 10 bytes
+    pub const SPACE: usize = 8
+        + 4
+        + 10
+        + 8
+        + 32
+        + 32
+        + 8
+        + 1
+        + (2 * (32 * 5 + 2 * 5 + 2 * 5 + 2 * 5 + 2 * 5 + 2 * 5 + 2 * 5 + 5 + 8))
+        + 1
+        + 8
+        + 1
+        + 1
+        + 1
+        + (1 + 4 + 4)
+        + (2 + 8 + 8 + 2)
+        + (2 + 2 + 2 + 2 + 1 + 32)
+        + (1 + 32 + 8 + 4 + 1)
+        + (8 * 2 + 1 + 1)
+        + (1 + 1 + 2 + 1 + 9)
+        + 2
+        + 8
+        + 32 * MAX_SESSION_DELEGATES
+        + 1
+        + 2
+        + 8;
+
+    /// Whether `key` may record kills: the authority or one of its delegates
+    pub fn is_operator(&self, key: Pubkey) -> bool {
+        key == self.authority || (key != Pubkey::default() && self.delegates.contains(&key))
+    }
+
+    /// Hands the session to another game server, delegates of the previous one are dropped
+    pub fn transfer_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+        require!(
+            new_authority != Pubkey::default(),
+            WagerError::InvalidSessionAuthority
+        );
+        self.authority = new_authority;
+        self.delegates = [Pubkey::default(); MAX_SESSION_DELEGATES];
+        Ok(())
+    }
+
+    /// Allows an operator to record kills
+    pub fn add_delegate(&mut self, delegate: Pubkey) -> Result<()> {
+        require!(
+            delegate != Pubkey::default() && !self.is_operator(delegate),
+            WagerError::InvalidSessionAuthority
+        );
+        let slot = self
+            .delegates
+            .iter_mut()
+            .find(|slot| **slot == Pubkey::default())
+            .ok_or(error!(WagerError::TooManyDelegates))?;
+        *slot = delegate;
+        Ok(())
+    }
+
+    /// Revokes an operator
+    pub fn remove_delegate(&mut self, delegate: Pubkey) -> Result<()> {
+        let slot = self
+            .delegates
+            .iter_mut()
+            .find(|slot| delegate != Pubkey::default() && **slot == delegate)
+            .ok_or(error!(WagerError::InvalidSessionAuthority))?;
+        *slot = Pubkey::default();
+        Ok(())
+    }
+
+    /// Fee kept from a payout of `amount`, rounded down
+    pub fn fee_on(&self, amount: u64) -> u64 {
+        // The fee is below 100%, so it always fits back into a u64
+        (amount as u128 * self.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
     /// Gets an empty slot for a player in the specified team
     pub fn get_player_empty_slot(&self, team: u8) -> Result<usize> {
         let player_count = self.game_mode.players_per_team();
@@ -103,12 +662,7 @@ impl GameSession {
     }
 
     pub fn is_pay_to_spawn(&self) -> bool {
-        matches!(
-            self.game_mode,
-            GameMode::PayToSpawnOneVsOne
-                | GameMode::PayToSpawnThreeVsThree
-                | GameMode::PayToSpawnFiveVsFive
-        )
+        self.game_mode.is_pay_to_spawn()
     }
 
     pub fn get_all_players(&self) -> Vec<Pubkey> {
@@ -117,40 +671,176 @@ impl GameSession {
         players
     }
 
-    pub fn get_player_index(&self, team: u8, player: Pubkey) -> Result<usize> {
-        match team {
-            0 => self
-                .team_a
-                .players
-                .iter()
-                .position(|p| *p == player)
-                .ok_or(error!(WagerError::PlayerNotFound)),
-            1 => self
-                .team_b
-                .players
-                .iter()
-                .position(|p| *p == player)
-                .ok_or(error!(WagerError::PlayerNotFound)),
+    /// Lists every joined player as (team, slot, player)
+    pub fn get_joined_players(&self) -> Vec<(u8, usize, Pubkey)> {
+        [(0u8, &self.team_a), (1u8, &self.team_b)]
+            .into_iter()
+            .flat_map(|(team_index, team)| {
+                team.players
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, player)| **player != Pubkey::default())
+                    .map(move |(slot, player)| (team_index, slot, *player))
+            })
+            .collect()
+    }
+
+    /// Gets the player occupying a slot, fails for empty or out of range slots
+    pub fn get_player(&self, team: u8, player_index: usize) -> Result<Pubkey> {
+        let team = match team {
+            0 => &self.team_a,
+            1 => &self.team_b,
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+        team.players
+            .get(player_index)
+            .filter(|player| **player != Pubkey::default())
+            .copied()
+            .ok_or(error!(WagerError::PlayerNotFound))
+    }
+
+    /// Lists every joined player that has not forfeited as (team, slot, player)
+    pub fn get_active_players(&self) -> Vec<(u8, usize, Pubkey)> {
+        self.get_joined_players()
+            .into_iter()
+            .filter(|(team, slot, _)| {
+                let team = if *team == 0 {
+                    &self.team_a
+                } else {
+                    &self.team_b
+                };
+                !team.player_forfeited[*slot]
+            })
+            .collect()
+    }
+
+    /// Players of the given team still in the match, empty and forfeited slots left out
+    pub fn team_players(&self, team: u8) -> Result<Vec<Pubkey>> {
+        require!(team == 0 || team == 1, WagerError::InvalidTeam);
+        Ok(self
+            .get_active_players()
+            .into_iter()
+            .filter(|(player_team, _, _)| *player_team == team)
+            .map(|(_, _, player)| player)
+            .collect())
+    }
+
+    /// Starts a lobby that is not full yet. No-shows are dropped and their
+    /// stake stays in the pot paid out at settlement, then the larger team is
+    /// trimmed from its last slots down to the size of the smaller one.
+    /// Returns the refunds owed to the trimmed players.
+    pub fn start_short(
+        &mut self,
+        min_players_per_team: u8,
+        no_shows: &[Pubkey],
+    ) -> Result<Vec<(Pubkey, u64)>> {
+        require!(
+            self.status == GameStatus::WaitingForPlayers,
+            WagerError::InvalidGameState
+        );
+        require!(
+            min_players_per_team > 0
+                && min_players_per_team as usize <= self.game_mode.players_per_team(),
+            WagerError::InvalidPlayerCount
+        );
+
+        for no_show in no_shows {
+            let (team, slot) = Self::single_slot(&self.player_slots(*no_show))?;
+            self.get_team_mut(team)?.clear_slot(slot);
+        }
+
+        let team_size = self.team_a.player_count().min(self.team_b.player_count());
+        require!(
+            team_size >= min_players_per_team as usize,
+            WagerError::NotEnoughPlayers
+        );
+
+        let mut refunds = Vec::new();
+        for team in [&mut self.team_a, &mut self.team_b] {
+            while team.player_count() > team_size {
+                let slot = team
+                    .players
+                    .iter()
+                    .rposition(|player| *player != Pubkey::default())
+                    .ok_or(error!(WagerError::PlayerNotFound))?;
+                refunds.push((team.players[slot], self.session_bet));
+                team.clear_slot(slot);
+            }
+        }
+
+        self.status = GameStatus::InProgress;
+        Ok(refunds)
+    }
+
+    /// Pay-to-spawn score of the player in the given slot
+    pub fn get_player_score(&self, team: u8, player_index: usize) -> Result<u64> {
+        let team = match team {
+            0 => &self.team_a,
+            1 => &self.team_b,
             _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+        Ok(self.payout_config.score(team, player_index))
+    }
+
+    /// Whether the wallet occupies any slot of the session
+    pub fn contains_player(&self, player: Pubkey) -> bool {
+        player != Pubkey::default() && self.get_all_players().contains(&player)
+    }
+
+    /// Checks that the wallet does not already hold a slot of the session
+    pub fn check_can_join(&self, player: Pubkey) -> Result<()> {
+        require!(
+            !self.contains_player(player),
+            WagerError::PlayerAlreadyJoined
+        );
+        Ok(())
+    }
+
+    /// Lists every slot the wallet occupies as (team, slot)
+    fn player_slots(&self, player: Pubkey) -> Vec<(u8, usize)> {
+        self.get_joined_players()
+            .into_iter()
+            .filter(|(_, _, p)| *p == player)
+            .map(|(team, slot, _)| (team, slot))
+            .collect()
+    }
+
+    /// Only slot of the wallet in `slots`, fails when it has none or several
+    fn single_slot(slots: &[(u8, usize)]) -> Result<(u8, usize)> {
+        match slots {
+            [slot] => Ok(*slot),
+            [] => Err(error!(WagerError::PlayerNotFound)),
+            _ => Err(error!(WagerError::AmbiguousPlayer)),
         }
     }
 
-    /// Gets the kill and death difference for a player in a team
+    /// Slot of the player in the given team, fails when the player
+    /// occupies several slots of it
+    pub fn get_player_index(&self, team: u8, player: Pubkey) -> Result<usize> {
+        require!(team == 0 || team == 1, WagerError::InvalidTeam);
+        let slots: Vec<(u8, usize)> = self
+            .player_slots(player)
+            .into_iter()
+            .filter(|(player_team, _)| *player_team == team)
+            .collect();
+        Self::single_slot(&slots).map(|(_, slot)| slot)
+    }
+
+    /// Gets the kills plus remaining spawns of a player, fails when the
+    /// player occupies several slots of the session
     pub fn get_kills_and_spawns(&self, player_pubkey: Pubkey) -> Result<u16> {
-        // search in both teams and return the kill and death difference
-        let team_a_index = self.team_a.players.iter().position(|p| *p == player_pubkey);
-        let team_b_index = self.team_b.players.iter().position(|p| *p == player_pubkey);
-        if let Some(team_a_index) = team_a_index {
-            Ok(self.team_a.player_kills[team_a_index] as u16
-                + self.team_a.player_spawns[team_a_index] as u16)
-        } else if let Some(team_b_index) = team_b_index {
-            Ok(self.team_b.player_kills[team_b_index] as u16
-                + self.team_b.player_spawns[team_b_index] as u16)
+        let (team, index) = Self::single_slot(&self.player_slots(player_pubkey))?;
+        let team = if team == 0 {
+            &self.team_a
         } else {
-            return Err(error!(WagerError::PlayerNotFound));
-        }
+            &self.team_b
+        };
+        team.player_kills[index]
+            .checked_add(team.player_spawns[index])
+            .ok_or(error!(WagerError::ArithmeticError))
     }
 
+    /// Records a kill between two players, inferring its kind from their teams
     pub fn add_kill(
         &mut self,
         killer_team: u8,
@@ -158,35 +848,565 @@ impl GameSession {
         victim_team: u8,
         victim: Pubkey,
     ) -> Result<()> {
+        let kill = self.kill_event(killer_team, killer, victim_team, victim)?;
+        self.apply_kill_event(&kill)
+    }
+
+    /// Builds the compact kill event of a kill between two players
+    pub fn kill_event(
+        &self,
+        killer_team: u8,
+        killer: Pubkey,
+        victim_team: u8,
+        victim: Pubkey,
+    ) -> Result<KillEvent> {
         let killer_player_index: usize = self.get_player_index(killer_team, killer)?;
         let victim_player_index: usize = self.get_player_index(victim_team, victim)?;
 
+        let kind = if killer_team != victim_team {
+            KillKind::Normal
+        } else if killer_player_index == victim_player_index {
+            KillKind::Suicide
+        } else {
+            KillKind::TeamKill
+        };
+
+        Ok(KillEvent {
+            kind,
+            killer_team,
+            killer_index: killer_player_index as u8,
+            victim_team,
+            victim_index: victim_player_index as u8,
+        })
+    }
+
+    /// Applies a kill event between occupied player slots according to its kind
+    pub fn apply_kill_event(&mut self, kill: &KillEvent) -> Result<()> {
+        let killer_player_index = kill.killer_index as usize;
+        let victim_player_index = kill.victim_index as usize;
+
+        // Environmental deaths have no killer
+        if kill.kind != KillKind::Environmental {
+            self.get_player(kill.killer_team, killer_player_index)?;
+        }
+        self.get_player(kill.victim_team, victim_player_index)?;
+
+        require!(self.is_playing(), WagerError::GameNotInProgress);
+
+        let same_team = kill.killer_team == kill.victim_team;
+        let same_player = same_team && killer_player_index == victim_player_index;
+        let valid = match kill.kind {
+            KillKind::Normal | KillKind::Assist => !same_team,
+            KillKind::Suicide => same_player,
+            KillKind::TeamKill => same_team && !same_player,
+            KillKind::Environmental => true,
+        };
+        require!(valid, WagerError::InvalidKillEvent);
+
+        // Work out the death first, a player out of spawns cannot die again
+        let death = if kill.kind == KillKind::Assist {
+            None
+        } else {
+            let victim_team = self.get_team_mut(kill.victim_team)?;
+            let spawns = victim_team.player_spawns[victim_player_index]
+                .checked_sub(1)
+                .ok_or(error!(WagerError::PlayerHasNoSpawns))?;
+            let deaths = checked_increment(victim_team.player_deaths[victim_player_index])?;
+            Some((spawns, deaths))
+        };
+
+        // Environmental deaths and suicides credit no killer, so its team is not looked up
+        if matches!(
+            kill.kind,
+            KillKind::Normal | KillKind::TeamKill | KillKind::Assist
+        ) {
+            let killer_team = self.get_team_mut(kill.killer_team)?;
+            let counter = match kill.kind {
+                KillKind::TeamKill => &mut killer_team.player_team_kills[killer_player_index],
+                // An assist credits the assisting player, the death comes with the kill itself
+                KillKind::Assist => &mut killer_team.player_assists[killer_player_index],
+                _ => &mut killer_team.player_kills[killer_player_index],
+            };
+            *counter = checked_increment(*counter)?;
+        }
+
+        if let Some((spawns, deaths)) = death {
+            let victim_team = self.get_team_mut(kill.victim_team)?;
+            victim_team.player_spawns[victim_player_index] = spawns;
+            victim_team.player_deaths[victim_player_index] = deaths;
+        }
+
+        Ok(())
+    }
+
+    /// Commits a new root of the match log, which may only grow until finalized
+    pub fn commit_match_log(
+        &mut self,
+        root: [u8; 32],
+        event_count: u64,
+        is_final: bool,
+    ) -> Result<()> {
         require!(
             self.status == GameStatus::InProgress,
             WagerError::GameNotInProgress
         );
+        let match_log = self
+            .match_log
+            .as_mut()
+            .ok_or(error!(WagerError::MatchLogNotEnabled))?;
+        require!(!match_log.finalized, WagerError::MatchLogFinalized);
+        require!(
+            event_count >= match_log.event_count,
+            WagerError::InvalidMatchLogCommit
+        );
 
-        match killer_team {
-            0 => self.team_a.player_kills[killer_player_index] += 1,
-            1 => self.team_b.player_kills[killer_player_index] += 1,
-            _ => return Err(error!(WagerError::InvalidTeam)),
+        match_log.root = root;
+        match_log.event_count = event_count;
+        match_log.commits = match_log
+            .commits
+            .checked_add(1)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        match_log.finalized = is_final;
+        Ok(())
+    }
+
+    /// Records the winner of the next round of the series, players still in
+    /// the match respawn for the next round while the series is undecided
+    pub fn record_series_round(&mut self, winning_team: u8) -> Result<()> {
+        require!(self.is_playing(), WagerError::GameNotInProgress);
+        let series = self
+            .series
+            .as_mut()
+            .ok_or(error!(WagerError::SeriesNotEnabled))?;
+        series.record_round(winning_team)?;
+
+        if series.winner().is_none() {
+            for (team, slot, _) in self.get_active_players() {
+                self.get_team_mut(team)?.player_spawns[slot] = INITIAL_SPAWNS;
+            }
         }
+        Ok(())
+    }
 
-        match victim_team {
-            0 => self.team_a.player_spawns[victim_player_index] -= 1,
-            1 => self.team_b.player_spawns[victim_player_index] -= 1,
-            _ => return Err(error!(WagerError::InvalidTeam)),
+    /// Whether the match is still being played, it ends early once a team forfeits it
+    pub fn is_playing(&self) -> bool {
+        self.status == GameStatus::InProgress && self.forfeit_winner.is_none()
+    }
+
+    /// Marks the player as forfeited, they keep their stats but get no winnings.
+    /// The match goes to the other team once the whole team has forfeited, or
+    /// right away when `end_match` concedes it. Returns whether the match ended.
+    pub fn forfeit_player(&mut self, team: u8, player: Pubkey, end_match: bool) -> Result<bool> {
+        require!(self.is_playing(), WagerError::GameNotInProgress);
+        let player_index = self.get_player_index(team, player)?;
+
+        let forfeiting_team = self.get_team_mut(team)?;
+        require!(
+            !forfeiting_team.player_forfeited[player_index],
+            WagerError::PlayerForfeited
+        );
+        forfeiting_team.player_forfeited[player_index] = true;
+
+        let match_ended = end_match || forfeiting_team.has_forfeited();
+        if match_ended {
+            self.forfeit_winner = Some(1 - team);
         }
+        Ok(match_ended)
+    }
+
+    /// Checks that the session may pay out `winning_team`: it must have active
+    /// players left, a match ended by forfeit goes to the team left standing and
+    /// a series must have been clinched by it
+    pub fn check_can_settle_for(&self, winning_team: u8) -> Result<()> {
+        let has_active_players = self
+            .get_active_players()
+            .iter()
+            .any(|(team, _, _)| *team == winning_team);
+        require!(has_active_players, WagerError::NoActivePlayers);
 
+        if let Some(forfeit_winner) = self.forfeit_winner {
+            require!(
+                forfeit_winner == winning_team,
+                WagerError::MatchForfeitedToOtherTeam
+            );
+            return Ok(());
+        }
+        if let Some(series) = self.series {
+            require!(
+                series.winner() == Some(winning_team),
+                WagerError::SeriesNotDecided
+            );
+        }
         Ok(())
     }
 
-    pub fn add_spawns(&mut self, team: u8, player_index: usize) -> Result<()> {
+    /// Whether the session may be settled, sessions with a match log need its final root
+    pub fn has_final_match_log(&self) -> bool {
+        match self.match_log {
+            Some(match_log) => match_log.finalized,
+            None => true,
+        }
+    }
+
+    /// Checks a serialized match event against the committed match log root
+    pub fn verify_match_event(&self, event: &[u8], proof: &[[u8; 32]]) -> bool {
+        self.match_log
+            .is_some_and(|match_log| verify_proof(&match_log.root, &leaf_hash(event), proof))
+    }
+
+    /// Gets a mutable reference to the specified team
+    pub fn get_team_mut(&mut self, team: u8) -> Result<&mut Team> {
         match team {
-            0 => self.team_a.player_spawns[player_index] += 10u16,
-            1 => self.team_b.player_spawns[player_index] += 10u16,
+            0 => Ok(&mut self.team_a),
+            1 => Ok(&mut self.team_b),
+            _ => Err(error!(WagerError::InvalidTeam)),
+        }
+    }
+
+    /// Total stakes of a full lobby, both teams included
+    pub fn total_pot(&self) -> Result<u64> {
+        self.session_bet
+            .checked_mul(self.game_mode.players_per_team() as u64)
+            .and_then(|team_stakes| team_stakes.checked_mul(2))
+            .ok_or(error!(WagerError::ArithmeticError))
+    }
+
+    /// Winner-takes-all payouts of the active players of `winning_team`. They
+    /// share every deposit the vault holds evenly, less the session fee. Stakes
+    /// of no-shows and of forfeited players, teammates included, go to them too.
+    /// The rounding remainder stays in the vault.
+    pub fn winner_payouts(&self, winning_team: u8) -> Result<Vec<(Pubkey, u64)>> {
+        self.check_can_settle_for(winning_team)?;
+        let winners = self.team_players(winning_team)?;
+
+        let share = self.held_deposits / winners.len() as u64;
+        let payout = share - self.fee_on(share);
+        Ok(winners.into_iter().map(|winner| (winner, payout)).collect())
+    }
+
+    /// Checks at creation that the largest vault balance and spawn count the
+    /// session can reach fit their integer types
+    pub fn validate_stakes(&self) -> Result<()> {
+        let total_pot = self
+            .total_pot()
+            .map_err(|_| error!(WagerError::InvalidBetAmount))?;
+        if !self.is_pay_to_spawn() {
+            return Ok(());
+        }
+
+        let players = self.game_mode.players_per_team() as u64 * 2;
+        self.spawn_config
+            .total_price(self.spawn_config.max_purchases)
+            .ok()
+            .and_then(|purchases| purchases.checked_mul(players))
+            .and_then(|purchases| purchases.checked_add(total_pot))
+            .ok_or(error!(WagerError::InvalidBetAmount))?;
+        self.spawn_config
+            .spawns_per_purchase
+            .checked_mul(self.spawn_config.max_purchases)
+            .and_then(|spawns| spawns.checked_add(INITIAL_SPAWNS))
+            .ok_or(error!(WagerError::InvalidSpawnConfig))?;
+        Ok(())
+    }
+
+    /// Freezes or unfreezes a session that has not been settled yet
+    pub fn set_frozen(&mut self, frozen: bool) -> Result<()> {
+        require!(
+            self.status != GameStatus::Completed,
+            WagerError::InvalidGameState
+        );
+        self.frozen = frozen;
+        Ok(())
+    }
+
+    /// Everything the player in the given slot paid into the vault,
+    /// the session bet plus all spawn purchases
+    pub fn player_deposit(&self, team: u8, player_index: usize) -> Result<u64> {
+        let purchases_made = match team {
+            0 => self.team_a.spawn_purchases[player_index],
+            1 => self.team_b.spawn_purchases[player_index],
             _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+        self.spawn_config
+            .total_price(purchases_made)?
+            .checked_add(self.session_bet)
+            .ok_or(error!(WagerError::ArithmeticError))
+    }
+
+    /// Refunds of a session cancelled before settlement, every joined player
+    /// gets back their full deposit
+    pub fn refunds(&self) -> Result<Vec<(Pubkey, u64)>> {
+        require!(
+            matches!(
+                self.status,
+                GameStatus::WaitingForPlayers | GameStatus::InProgress
+            ),
+            WagerError::InvalidGameState
+        );
+        self.get_joined_players()
+            .into_iter()
+            .map(|(team, slot, player)| Ok((player, self.player_deposit(team, slot)?)))
+            .collect()
+    }
+
+    /// Price of the next spawn purchase of a player, fails once the purchase limit is reached
+    pub fn spawn_purchase_price(&self, team: u8, player_index: usize) -> Result<u64> {
+        let purchases_made = match team {
+            0 => self.team_a.spawn_purchases[player_index],
+            1 => self.team_b.spawn_purchases[player_index],
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+        self.spawn_config.purchase_price(purchases_made)
+    }
+
+    /// Adds a player payment into the vault to the deposits it holds
+    pub fn hold_deposit(&mut self, amount: u64) -> Result<()> {
+        self.held_deposits = self
+            .held_deposits
+            .checked_add(amount)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        Ok(())
+    }
+
+    /// Marks the session completed once its pot was paid out, every deposit
+    /// left in the vault was shared and only fees and rounding remain
+    pub fn complete_settlement(&mut self) {
+        self.held_deposits = 0;
+        self.status = GameStatus::Completed;
+    }
+
+    /// Checks that the vault of the session may be swept: the session must be
+    /// completed and every deposit refunded or paid out
+    pub fn check_can_sweep(&self) -> Result<()> {
+        require!(
+            self.status == GameStatus::Completed,
+            WagerError::InvalidGameState
+        );
+        require!(self.held_deposits == 0, WagerError::UnsettledDeposits);
+        Ok(())
+    }
+
+    /// Removes refunded or paid out deposits from those the vault holds
+    pub fn release_deposits(&mut self, amount: u64) -> Result<()> {
+        self.held_deposits = self
+            .held_deposits
+            .checked_sub(amount)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        Ok(())
+    }
+
+    pub fn add_spawns(&mut self, team: u8, player_index: usize) -> Result<()> {
+        let spawn_config = self.spawn_config;
+        let team = self.get_team_mut(team)?;
+        require!(
+            !team.player_forfeited[player_index],
+            WagerError::PlayerForfeited
+        );
+        require!(
+            team.spawn_purchases[player_index] < spawn_config.max_purchases,
+            WagerError::SpawnPurchaseLimitReached
+        );
+
+        team.player_spawns[player_index] = team.player_spawns[player_index]
+            .checked_add(spawn_config.spawns_per_purchase)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        team.spawn_purchases[player_index] = checked_increment(team.spawn_purchases[player_index])?;
+        Ok(())
+    }
+}
+
+/// Lifetime statistics of a player across all settled sessions
+#[account]
+pub struct PlayerProfile {
+    pub player: Pubkey,     // Wallet the profile belongs to
+    pub games_played: u32,  // Number of settled sessions played
+    pub wins: u32,          // Number of winner-takes-all sessions won
+    pub losses: u32,        // Number of winner-takes-all sessions lost
+    pub total_wagered: u64, // Total amount staked in settled sessions, including spawn purchases
+    pub total_won: u64,     // Total amount received from payouts
+    pub total_kills: u64,   // Total number of kills
+    pub total_deaths: u64,  // Total number of deaths
+    pub total_assists: u64, // Total number of assists
+    pub rating: u32,        // Skill rating, updated on winner-takes-all settlements
+    pub bump: u8,           // PDA bump
+}
+
+impl PlayerProfile {
+    /// Sets up a freshly created profile
+    pub fn initialize(&mut self, player: Pubkey, bump: u8) {
+        self.player = player;
+        self.rating = DEFAULT_RATING;
+        self.bump = bump;
+    }
+
+    /// Moves the rating by `change` points without going below zero
+    pub fn apply_rating_change(&mut self, change: i64) {
+        self.rating = (self.rating as i64 + change).clamp(0, u32::MAX as i64) as u32;
+    }
+
+    /// Adds the deposit of a settled session to the lifetime total
+    pub fn record_wager(&mut self, amount: u64) -> Result<()> {
+        self.total_wagered = self
+            .total_wagered
+            .checked_add(amount)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        Ok(())
+    }
+
+    /// Adds the outcome of a settled session, `won` is None when the mode has no winning team
+    pub fn record_game(
+        &mut self,
+        won: Option<bool>,
+        kills: u16,
+        deaths: u16,
+        assists: u16,
+        payout: u64,
+    ) -> Result<()> {
+        self.games_played = self
+            .games_played
+            .checked_add(1)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        match won {
+            Some(true) => {
+                self.wins = self
+                    .wins
+                    .checked_add(1)
+                    .ok_or(error!(WagerError::ArithmeticError))?
+            }
+            Some(false) => {
+                self.losses = self
+                    .losses
+                    .checked_add(1)
+                    .ok_or(error!(WagerError::ArithmeticError))?
+            }
+            None => {}
         }
+        self.total_won = self
+            .total_won
+            .checked_add(payout)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        self.total_kills = self
+            .total_kills
+            .checked_add(kills as u64)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        self.total_deaths = self
+            .total_deaths
+            .checked_add(deaths as u64)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        self.total_assists = self
+            .total_assists
+            .checked_add(assists as u64)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        Ok(())
+    }
+
+    /// Adds the deposit and outcome of the player in `slot` of `team` once the
+    /// session settled, then moves the rating by the `average_ratings` of both
+    /// teams. Forfeited players lose even when their team won.
+    /// Returns the outcome, None when the mode has no winning team.
+    pub fn record_session(
+        &mut self,
+        game_session: &GameSession,
+        session_result: &SessionResult,
+        team: u8,
+        slot: usize,
+        average_ratings: [u32; 2],
+    ) -> Result<Option<bool>> {
+        let (game_team, team_result) = match team {
+            0 => (&game_session.team_a, &session_result.team_a),
+            1 => (&game_session.team_b, &session_result.team_b),
+            _ => return Err(error!(WagerError::InvalidTeam)),
+        };
+        let won = session_result
+            .winning_team
+            .map(|winner| winner == team && !game_team.player_forfeited[slot]);
+
+        self.record_wager(game_session.player_deposit(team, slot)?)?;
+        self.record_game(
+            won,
+            game_team.player_kills[slot],
+            game_team.player_deaths[slot],
+            game_team.player_assists[slot],
+            team_result.payouts[slot],
+        )?;
+
+        if let Some(won) = won {
+            let team_rating = average_ratings[team as usize];
+            let opponent_rating = average_ratings[1 - team as usize];
+            self.apply_rating_change(rating_change(team_rating, opponent_rating, won));
+        }
+        Ok(won)
+    }
+}
+
+/// Final standing of a team, frozen at settlement
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct TeamResult {
+    pub players: [Pubkey; 5],    // Array of player public keys
+    pub payouts: [u64; 5],       // Amount paid out to each player
+    pub player_kills: [u16; 5],  // Final number of kills for each player
+    pub player_spawns: [u16; 5], // Number of spawns left for each player
+}
+
+impl From<&Team> for TeamResult {
+    fn from(team: &Team) -> Self {
+        Self {
+            players: team.players,
+            payouts: [0; 5],
+            player_kills: team.player_kills,
+            player_spawns: team.player_spawns,
+        }
+    }
+}
+
+/// Receipt of a settled game session, kept after the session itself is closed
+#[account]
+pub struct SessionResult {
+    pub session_id: String,       // Identifier of the settled game
+    pub game_session: Pubkey,     // Game session this result was taken from
+    pub game_mode: GameMode,      // Game configuration of the session
+    pub winning_team: Option<u8>, // Winning team, None for pay-to-spawn sessions
+    pub team_a: TeamResult,       // Final standing of the first team
+    pub team_b: TeamResult,       // Final standing of the second team
+    pub total_paid: u64,          // Sum of all payouts
+    pub fee_taken: u64,           // Amount left in the vault after every transfer
+    pub settled_at: i64,          // Settlement timestamp
+    pub bump: u8,                 // PDA bump
+}
+
+impl SessionResult {
+    /// Copies the final scoreboard of a game session into the result
+    pub fn record_scoreboard(
+        &mut self,
+        game_session_key: Pubkey,
+        game_session: &GameSession,
+        winning_team: Option<u8>,
+    ) {
+        self.session_id = game_session.session_id.clone();
+        self.game_session = game_session_key;
+        self.game_mode = game_session.game_mode;
+        self.winning_team = winning_team;
+        self.team_a = TeamResult::from(&game_session.team_a);
+        self.team_b = TeamResult::from(&game_session.team_b);
+    }
+
+    /// Adds a payout to the slot of the given player
+    pub fn record_payout(&mut self, player: Pubkey, amount: u64) -> Result<()> {
+        let payout = if let Some(index) = self.team_a.players.iter().position(|p| *p == player) {
+            &mut self.team_a.payouts[index]
+        } else if let Some(index) = self.team_b.players.iter().position(|p| *p == player) {
+            &mut self.team_b.payouts[index]
+        } else {
+            return Err(error!(WagerError::PlayerNotFound));
+        };
+
+        *payout = payout
+            .checked_add(amount)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+        self.total_paid = self
+            .total_paid
+            .checked_add(amount)
+            .ok_or(error!(WagerError::ArithmeticError))?;
         Ok(())
     }
 }
@@ -195,3 +1415,241 @@ impl GameSession {
 fn is_team_full_error(error: &Error) -> bool {
     error.to_string().contains("TeamIsFull")
 }
+
+/// Longest tournament id, short enough for child session ids to fit a session account
+pub const MAX_TOURNAMENT_ID_LEN: usize = 6;
+
+/// Largest single elimination bracket
+pub const MAX_TOURNAMENT_PLAYERS: usize = 16;
+
+/// Status of a tournament
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Default)]
+pub enum TournamentStatus {
+    #[default]
+    Registering, // Waiting for entrants to fill the bracket
+    InProgress, // Bracket is being played
+    Completed,  // Champion was paid the prize pool
+    Cancelled,  // Entry fees were refunded to every entrant
+}
+
+/// Single elimination tournament of 1v1 winner takes all sessions
+#[account]
+pub struct Tournament {
+    pub tournament_id: String, // Unique identifier of the tournament
+    pub authority: Pubkey,     // Game server running the tournament and its sessions
+    pub entry_fee: u64,        // Fee paid by every entrant into the prize pool
+    pub bracket_size: u8,      // Number of entrants, a power of two
+    pub entrant_count: u8,     // Number of entrants registered so far
+    pub bracket: [Pubkey; 2 * MAX_TOURNAMENT_PLAYERS - 1], // Players of every round, entrants first and champion last
+    pub match_sessions: [Pubkey; MAX_TOURNAMENT_PLAYERS - 1], // Session of every bracket match, in bracket order
+    pub status: TournamentStatus,                             // Current tournament state
+    pub prize_pool: u64,                                      // Entry fees collected
+    pub bump: u8,                                             // PDA bump
+    pub vault_bump: u8,                                       // Prize vault PDA bump
+}
+
+impl Tournament {
+    /// Checks the id, entry fee and bracket size of a new tournament, the
+    /// entry fee is held to the same limits as a session bet
+    pub fn validate(
+        tournament_id: &str,
+        entry_fee: u64,
+        bracket_size: u8,
+        bet_limits: &BetLimits,
+    ) -> Result<()> {
+        bet_limits.check(entry_fee)?;
+        require!(
+            !tournament_id.is_empty() && tournament_id.len() <= MAX_TOURNAMENT_ID_LEN,
+            WagerError::InvalidTournamentId
+        );
+        require!(
+            bracket_size >= 2
+                && bracket_size as usize <= MAX_TOURNAMENT_PLAYERS
+                && bracket_size.is_power_of_two(),
+            WagerError::InvalidBracketSize
+        );
+        Ok(())
+    }
+
+    /// Number of rounds until a champion is decided
+    pub fn rounds(&self) -> u8 {
+        self.bracket_size.trailing_zeros() as u8
+    }
+
+    /// Index of the first bracket slot of a round
+    fn round_offset(&self, round: u8) -> usize {
+        (0..round).map(|r| (self.bracket_size as usize) >> r).sum()
+    }
+
+    /// Registers an entrant, starting the tournament once the bracket is full
+    pub fn add_entrant(&mut self, player: Pubkey) -> Result<()> {
+        require!(
+            self.status == TournamentStatus::Registering,
+            WagerError::InvalidGameState
+        );
+        let entrants = &self.bracket[..self.entrant_count as usize];
+        require!(!entrants.contains(&player), WagerError::AlreadyEntered);
+
+        self.bracket[self.entrant_count as usize] = player;
+        self.entrant_count += 1;
+        self.prize_pool = self
+            .prize_pool
+            .checked_add(self.entry_fee)
+            .ok_or(error!(WagerError::ArithmeticError))?;
+
+        if self.entrant_count == self.bracket_size {
+            self.status = TournamentStatus::InProgress;
+        }
+        Ok(())
+    }
+
+    /// Cancels a tournament that has not paid out yet. Returns the refund of
+    /// the entry fee owed to every entrant, eliminated ones included.
+    pub fn cancel(&mut self) -> Result<Vec<(Pubkey, u64)>> {
+        require!(
+            matches!(
+                self.status,
+                TournamentStatus::Registering | TournamentStatus::InProgress
+            ),
+            WagerError::InvalidGameState
+        );
+        let refunds = self.bracket[..self.entrant_count as usize]
+            .iter()
+            .map(|entrant| (*entrant, self.entry_fee))
+            .collect();
+        self.status = TournamentStatus::Cancelled;
+        self.prize_pool = 0;
+        Ok(refunds)
+    }
+
+    /// Session id of a bracket match. The suffix has a fixed length, so ids of
+    /// different tournaments never collide.
+    pub fn match_session_id(&self, round: u8, match_index: u8) -> String {
+        format!("{}-{:x}{:x}", self.tournament_id, round, match_index)
+    }
+
+    /// Both players of a bracket match, once the previous round decided them
+    pub fn match_players(&self, round: u8, match_index: u8) -> Result<(Pubkey, Pubkey)> {
+        require!(
+            self.status == TournamentStatus::InProgress,
+            WagerError::InvalidGameState
+        );
+        require!(
+            round < self.rounds()
+                && (match_index as usize) < (self.bracket_size as usize) >> (round + 1),
+            WagerError::InvalidBracketMatch
+        );
+
+        let slot = self.round_offset(round) + 2 * match_index as usize;
+        let (player_a, player_b) = (self.bracket[slot], self.bracket[slot + 1]);
+        require!(
+            player_a != Pubkey::default() && player_b != Pubkey::default(),
+            WagerError::BracketMatchNotReady
+        );
+        Ok((player_a, player_b))
+    }
+
+    /// Index of a bracket match in `match_sessions`
+    pub fn match_number(&self, round: u8, match_index: u8) -> usize {
+        self.round_offset(round + 1) + match_index as usize - self.bracket_size as usize
+    }
+
+    /// Records the session created for a bracket match, once
+    pub fn set_match_session(
+        &mut self,
+        round: u8,
+        match_index: u8,
+        game_session: Pubkey,
+    ) -> Result<()> {
+        self.match_players(round, match_index)?;
+        let match_number = self.match_number(round, match_index);
+        require!(
+            self.match_sessions[match_number] == Pubkey::default(),
+            WagerError::BracketMatchAlreadyCreated
+        );
+        self.match_sessions[match_number] = game_session;
+        Ok(())
+    }
+
+    /// Moves the winner of a bracket match to the next round. Returns the
+    /// champion once the final was decided.
+    pub fn advance(
+        &mut self,
+        round: u8,
+        match_index: u8,
+        winner: Pubkey,
+    ) -> Result<Option<Pubkey>> {
+        let (player_a, player_b) = self.match_players(round, match_index)?;
+        require!(
+            winner == player_a || winner == player_b,
+            WagerError::InvalidWinner
+        );
+
+        let slot = self.round_offset(round + 1) + match_index as usize;
+        require!(
+            self.bracket[slot] == Pubkey::default(),
+            WagerError::BracketMatchAlreadyAdvanced
+        );
+        self.bracket[slot] = winner;
+
+        if round + 1 == self.rounds() {
+            self.status = TournamentStatus::Completed;
+            return Ok(Some(winner));
+        }
+        Ok(None)
+    }
+}
+
+/// Team of a session created before sessions were addressed by authority and index
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct LegacyTeam {
+    pub players: [Pubkey; 5],    // Array of player public keys
+    pub total_bet: u64,          // Total amount bet by team (in lamports)
+    pub player_spawns: [u16; 5], // Number of spawns remaining for each player
+    pub player_kills: [u16; 5],  // Number of kills for each player
+}
+
+/// Game session created before sessions were addressed by authority and index,
+/// stored at `[b"game_session", session_id]`. Legacy sessions can only be
+/// refunded and closed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyGameSession {
+    pub session_id: String,  // Unique identifier for the game
+    pub authority: Pubkey,   // Creator of the game session
+    pub session_bet: u64,    // Required bet amount per player
+    pub game_mode: GameMode, // Game configuration (1v1, 2v2, 5v5)
+    pub team_a: LegacyTeam,  // First team
+    pub team_b: LegacyTeam,  // Second team
+    pub status: GameStatus,  // Current game state
+    pub created_at: i64,     // Creation timestamp
+    pub bump: u8,            // PDA bump
+    pub vault_bump: u8,      // Vault PDA bump
+    pub vault_token_bump: u8,
+}
+
+impl LegacyGameSession {
+    /// Reads a legacy session from a game session account of this program
+    pub fn try_from_account(account: &AccountInfo) -> Result<Self> {
+        require!(
+            account.owner == &crate::ID,
+            WagerError::InvalidLegacySession
+        );
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() > 8 && data[..8] == GameSession::DISCRIMINATOR,
+            WagerError::InvalidLegacySession
+        );
+        Self::deserialize(&mut &data[8..]).map_err(|_| error!(WagerError::InvalidLegacySession))
+    }
+
+    /// Players that joined the session, team A first
+    pub fn joined_players(&self) -> Vec<Pubkey> {
+        self.team_a
+            .players
+            .iter()
+            .chain(self.team_b.players.iter())
+            .copied()
+            .filter(|player| *player != Pubkey::default())
+            .collect()
+    }
+}
@@ -21,3 +21,10 @@ pub fn transfer_spl_tokens<'info>(
 
     Ok(())
 }
+
+/// Whether remaining accounts come as (player, token account) pairs,
+/// `usize::is_multiple_of` is newer than the rustc of the SBF toolchain
+#[allow(clippy::manual_is_multiple_of)]
+pub fn is_paired<T>(accounts: &[T]) -> bool {
+    accounts.len() % 2 == 0
+}
@@ -8,6 +8,7 @@ import {
   generateSessionId,
   deriveGameSessionPDA,
   deriveVaultPDA,
+  nextSessionIndex,
   loadKeypair,
   getVaultTokenAccount,
   setupTestAccounts,
@@ -31,14 +32,15 @@ describe("Game Session Creation", () => {
 
   it("Successfully creates a game session with winner-takes-all 1v1 mode", async () => {
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
+    const [vaultPda] = deriveVaultPDA(program.programId, gameServer.publicKey, sessionIndex);
     
     const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
 
     await program.methods
-      .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
+      .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} }, null, null, null, false, null)
       .accounts({
         gameServer: gameServer.publicKey,
       })
@@ -52,14 +54,15 @@ describe("Game Session Creation", () => {
 
   it("Successfully creates a game session with winner-takes-all 3v3 mode", async () => {
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
+    const [vaultPda] = deriveVaultPDA(program.programId, gameServer.publicKey, sessionIndex);
     
     const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
 
     await program.methods
-      .createGameSession(sessionId, betAmount, { winnerTakesAllThreeVsThree: {} })
+      .createGameSession(sessionId, betAmount, { winnerTakesAllThreeVsThree: {} }, null, null, null, false, null)
       .accounts({
         gameServer: gameServer.publicKey,
       })
@@ -73,14 +76,15 @@ describe("Game Session Creation", () => {
 
   it("Successfully creates a game session with winner-takes-all 5v5 mode", async () => {
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
+    const [vaultPda] = deriveVaultPDA(program.programId, gameServer.publicKey, sessionIndex);
     
     const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
 
     await program.methods
-      .createGameSession(sessionId, betAmount, { winnerTakesAllFiveVsFive: {} })
+      .createGameSession(sessionId, betAmount, { winnerTakesAllFiveVsFive: {} }, null, null, null, false, null)
       .accounts({
         gameServer: gameServer.publicKey,
       })
@@ -94,14 +98,15 @@ describe("Game Session Creation", () => {
 
   it("Successfully creates a game session with pay-to-spawn 1v1 mode", async () => {
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
+    const [vaultPda] = deriveVaultPDA(program.programId, gameServer.publicKey, sessionIndex);
     
     const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
 
     await program.methods
-      .createGameSession(sessionId, betAmount, { payToSpawnOneVsOne: {} })
+      .createGameSession(sessionId, betAmount, { payToSpawnOneVsOne: {} }, null, null, null, false, null)
       .accounts({
         gameServer: gameServer.publicKey,
       })
@@ -115,14 +120,15 @@ describe("Game Session Creation", () => {
 
   it("Successfully creates a game session with pay-to-spawn 3v3 mode", async () => {
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
+    const [vaultPda] = deriveVaultPDA(program.programId, gameServer.publicKey, sessionIndex);
     
     const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
 
     await program.methods
-      .createGameSession(sessionId, betAmount, { payToSpawnThreeVsThree: {} })
+      .createGameSession(sessionId, betAmount, { payToSpawnThreeVsThree: {} }, null, null, null, false, null)
       .accounts({
         gameServer: gameServer.publicKey,
       })
@@ -136,14 +142,15 @@ describe("Game Session Creation", () => {
 
   it("Successfully creates a game session with pay-to-spawn 5v5 mode", async () => {
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
+    const [vaultPda] = deriveVaultPDA(program.programId, gameServer.publicKey, sessionIndex);
     
     const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
 
     await program.methods
-      .createGameSession(sessionId, betAmount, { payToSpawnFiveVsFive: {} })
+      .createGameSession(sessionId, betAmount, { payToSpawnFiveVsFive: {} }, null, null, null, false, null)
       .accounts({
         gameServer: gameServer.publicKey,
       })
@@ -156,61 +163,50 @@ describe("Game Session Creation", () => {
   });
 
   it("Fails to create game session with zero bet amount", async () => {
+    const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
+    const betAmount = new BN(0);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
 
-    // const sessionId = generateSessionId();
-    // const betAmount = new BN(-100); 
-    // const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
-    // const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
-    
-    // const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
-  
-    // try {
-    //   let tx = await program.methods
-    //   .createGameSession(sessionId, betAmount, { winnerTakesAllFiveVsFive: {} })
-    //   .accounts({
-    //     gameServer: gameServer.publicKey,
-    //   })
-    //   .signers([gameServer])
-    //   .rpc(confirmOptions);
-
-    //   const account = await program.account.gameSession.fetch(gameSessionPda);
-    //   console.log(account.sessionBet.toString());
-    // } catch (e) {
-    //   assert.include(e.toString(), "Error Code: InvalidBetAmount");
-    // }
+    try {
+      await program.methods
+        .createGameSession(sessionId, betAmount, { winnerTakesAllFiveVsFive: {} }, null, null, null, false, null)
+        .accounts({
+          gameServer: gameServer.publicKey,
+        })
+        .signers([gameServer])
+        .rpc(confirmOptions);
+      assert.fail("Zero bet session should not be created");
+    } catch (e) {
+      assert.include(e.toString(), "Error Code: InvalidBetAmount");
+    }
+
+    const account = await program.account.gameSession.fetchNullable(gameSessionPda);
+    assert.isNull(account);
   });
 
-  it("Fails to create duplicate game session", async () => {
+  it("Gives sessions with the same id distinct indexes", async () => {
     const sessionId = generateSessionId();
-    const betAmount = new BN(100000000); // Changed from -100 to a valid amount
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
-    
-    const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
-
-    // First creation should succeed
-    await program.methods
-      .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
-      .accounts({
-        gameServer: gameServer.publicKey,
-      })
-      .signers([gameServer])
-      .rpc(confirmOptions);
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
+    const betAmount = new BN(100000000);
 
-    try {
-      // Second creation should fail
+    for (let i = 0; i < 2; i++) {
       await program.methods
-        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} }, null, null, null, false, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
         .signers([gameServer])
         .rpc(confirmOptions);
-      assert.fail("Should have failed with duplicate session");
-    } catch (e) {
-      assert.include(e.toString(), "Transaction simulation failed");
-      assert.include(e.logs.join('\n'), "account Address");
-      assert.include(e.logs.join('\n'), "already in use");
     }
+
+    // The human-readable id is only metadata, each session is addressed by its index
+    const [firstPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
+    const [secondPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex.addn(1));
+    const first = await program.account.gameSession.fetch(firstPda);
+    const second = await program.account.gameSession.fetch(secondPda);
+    assert.equal(first.sessionId, sessionId);
+    assert.equal(second.sessionId, sessionId);
+    assert.ok(second.sessionIndex.eq(sessionIndex.addn(1)));
   });
 }); 
\ No newline at end of file
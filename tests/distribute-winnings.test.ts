@@ -8,13 +8,15 @@
     generateSessionId,
     deriveGameSessionPDA,
     deriveVaultPDA,
+    nextSessionIndex,
     loadKeypair,
     setupTokenAccount,
     setupTestAccounts,
     getBalance,
     getVaultTokenAccount,
     TOKEN_ID,
-    getTokenBalance
+    getTokenBalance,
+    playerProfileAccounts
   } from "./utils";
   import { PublicKey } from "@solana/web3.js";
   import * as borsh from "borsh";
@@ -98,6 +100,7 @@
     it("Successfully distributes winnings to winning team", async () => {
       console.log("\n=== Starting distribute winnings test ===");
       const sessionId = generateSessionId();
+      const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
       const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
       console.log(`Session ID: ${sessionId}`);
       console.log(`Bet amount: ${betAmount.toString()} (0.1 tokens)`);
@@ -113,7 +116,7 @@
       // Create game session and join users
       console.log("\nCreating game session...");
       const tx = await program.methods
-        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} }, null, null, null, false, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -147,7 +150,7 @@
       // Join users
       console.log("\nJoining user 1...");
       const joinUser1Tx = await program.methods
-        .joinUser(sessionId, 0)
+        .joinUser(sessionIndex, 0)
         .accounts({
           user: user1.publicKey,
           gameServer: gameServer.publicKey,
@@ -159,7 +162,7 @@
 
       console.log("\nJoining user 2...");
       const joinUser2Tx = await program.methods
-        .joinUser(sessionId, 1)
+        .joinUser(sessionIndex, 1)
         .accounts({
           user: user2.publicKey,
           gameServer: gameServer.publicKey,
@@ -176,9 +179,10 @@
       // Distribute winnings
       console.log("\nDistributing winnings...");
       const txDistribute = await program.methods
-        .distributeWinnings(sessionId, 0)
+        .distributeWinnings(sessionIndex, 0)
         .accounts({
           gameServer: gameServer.publicKey,
+          remainderTokenAccount: null,
         })
         .remainingAccounts([
           {
@@ -191,6 +195,7 @@
             isSigner: false,
             isWritable: true,
           },
+          ...playerProfileAccounts(program.programId, [user1.publicKey, user2.publicKey]),
         ])
         .signers([gameServer])
         .rpc(confirmOptions);
@@ -211,6 +216,7 @@
     it("Successfully distributes winnings to winning team with 2v2", async () => {
       console.log("\n=== Starting distribute winnings test ===");
       const sessionId = generateSessionId();
+      const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
       const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
       console.log(`Session ID: ${sessionId}`);
       console.log(`Bet amount: ${betAmount.toString()} (0.1 tokens)`);
@@ -228,7 +234,7 @@
       // Create game session and join users
       console.log("\nCreating game session...");
       const tx = await program.methods
-        .createGameSession(sessionId, betAmount, { winnerTakesAllThreeVsThree: {} })
+        .createGameSession(sessionId, betAmount, { winnerTakesAllThreeVsThree: {} }, null, null, null, false, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -271,7 +277,7 @@
       // Join users
       console.log("\nJoining user 1...");
       const joinUser1Tx = await program.methods
-        .joinUser(sessionId, 0)
+        .joinUser(sessionIndex, 0)
         .accounts({
           user: user1.publicKey,
           gameServer: gameServer.publicKey,
@@ -283,7 +289,7 @@
 
       console.log("\nJoining user 2...");
       const joinUser2Tx = await program.methods
-        .joinUser(sessionId, 1)
+        .joinUser(sessionIndex, 1)
         .accounts({
           user: user2.publicKey,
           gameServer: gameServer.publicKey,
@@ -295,7 +301,7 @@
 
       console.log("\nJoining user 3...");
       const joinUser3Tx = await program.methods
-        .joinUser(sessionId, 0)
+        .joinUser(sessionIndex, 0)
         .accounts({
           user: user3.publicKey,
           gameServer: gameServer.publicKey,
@@ -307,7 +313,7 @@
 
       console.log("\nJoining user 4...");
       const joinUser4Tx = await program.methods
-        .joinUser(sessionId, 1)
+        .joinUser(sessionIndex, 1)
         .accounts({
           user: user4.publicKey,
           gameServer: gameServer.publicKey,
@@ -319,7 +325,7 @@
 
       console.log("Joining user 5...");
       const joinUser5Tx = await program.methods
-        .joinUser(sessionId, 0)
+        .joinUser(sessionIndex, 0)
         .accounts({
           user: user5.publicKey,
           gameServer: gameServer.publicKey,
@@ -329,7 +335,7 @@
         .rpc(confirmOptions);
       console.log("User 5 joined successfully with tx: ", joinUser5Tx);
 
-      const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
+      const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
       const account = await program.account.gameSession.fetch(gameSessionPda);
       console.log("\nGame session state after join:");
       console.log("Team A players:", account.teamA.players.map(p => p.toString()));
@@ -339,7 +345,7 @@
 
       console.log("Joining user 6...");
       const joinUser6Tx = await program.methods
-        .joinUser(sessionId, 1)
+        .joinUser(sessionIndex, 1)
         .accounts({
           user: user6.publicKey,
           gameServer: gameServer.publicKey,
@@ -361,9 +367,10 @@
       // Distribute winnings
       console.log("\nDistributing winnings...");
       const txDistribute = await program.methods
-        .distributeWinnings(sessionId, 0)
+        .distributeWinnings(sessionIndex, 0)
         .accounts({
           gameServer: gameServer.publicKey,
+          remainderTokenAccount: null,
         })
         .remainingAccounts([
           {
@@ -396,6 +403,14 @@
             isSigner: false,
             isWritable: true,
           },
+          ...playerProfileAccounts(program.programId, [
+            user1.publicKey,
+            user3.publicKey,
+            user5.publicKey,
+            user2.publicKey,
+            user4.publicKey,
+            user6.publicKey,
+          ]),
         ])
         .signers([gameServer])
         .rpc(confirmOptions);
@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { WagerProgram } from "../app/src/app/types/wager_program";
 import { describe } from "mocha";
+import { ensureGlobalConfig } from "./utils";
 
 describe("wager-program", () => {
   // Configure the client to use the local cluster
@@ -28,6 +29,7 @@ describe("wager-program", () => {
   before(async () => {
     // Add any setup that should run before all tests
     console.log("Setting up tests...");
+    await ensureGlobalConfig(program, provider.wallet.publicKey);
   });
 
   after(async () => {
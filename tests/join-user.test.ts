@@ -8,6 +8,7 @@ import {
   generateSessionId,
   deriveGameSessionPDA,
   deriveVaultPDA,
+  nextSessionIndex,
   getBalance,
   TOKEN_ID,
   getTokenBalance,
@@ -106,15 +107,16 @@ describe("Join User Tests", () => {
   it("Successfully joins users to both teams", async () => {
     console.log("\nStarting join user test...");
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     console.log("Session ID:", sessionId);
     
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
     console.log("Bet amount:", betAmount.toString());
 
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
     console.log("Game session PDA:", gameSessionPda.toString());
 
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
+    const [vaultPda] = deriveVaultPDA(program.programId, gameServer.publicKey, sessionIndex);
     console.log("Vault PDA:", vaultPda.toString());
 
     // Get vault token account address
@@ -131,7 +133,7 @@ describe("Join User Tests", () => {
     console.log("\nCreating game session...");
     try {
       await program.methods
-        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} })
+        .createGameSession(sessionId, betAmount, { winnerTakesAllOneVsOne: {} }, null, null, null, false, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -149,7 +151,7 @@ describe("Join User Tests", () => {
       console.log("user1 balance: ", await getTokenBalance(provider.connection, user1TokenAccount));
 
       await program.methods
-        .joinUser(sessionId, 0)
+        .joinUser(sessionIndex, 0)
         .accounts({
           user: user1.publicKey,
           gameServer: gameServer.publicKey,
@@ -171,7 +173,7 @@ describe("Join User Tests", () => {
     console.log("\nJoining user2 to team...");
     try {
       await program.methods
-        .joinUser(sessionId, 1)
+        .joinUser(sessionIndex, 1)
         .accounts({
           user: user2.publicKey,
           gameServer: gameServer.publicKey,
@@ -194,12 +196,13 @@ describe("Join User Tests", () => {
   // Test case for pay-to-spawn 1v1 mode
   it("Successfully joins users to both teams in pay-to-spawn 1v1 mode", async () => {
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
 
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
     console.log("Game session PDA:", gameSessionPda.toString());
 
-    const [vaultPda] = deriveVaultPDA(program.programId, gameSessionPda);
+    const [vaultPda] = deriveVaultPDA(program.programId, gameServer.publicKey, sessionIndex);
     console.log("Vault PDA:", vaultPda.toString());
 
     const vaultTokenAccount = await getVaultTokenAccount(TOKEN_ID, vaultPda);
@@ -211,7 +214,7 @@ describe("Join User Tests", () => {
     console.log("\nCreating game session...");
     try {
       await program.methods
-        .createGameSession(sessionId, betAmount, { payToSpawnOneVsOne: {} })
+        .createGameSession(sessionId, betAmount, { payToSpawnOneVsOne: {} }, null, null, null, false, null)
         .accounts({
           gameServer: gameServer.publicKey,
         })
@@ -229,7 +232,7 @@ describe("Join User Tests", () => {
       console.log("user1 balance: ", await getTokenBalance(provider.connection, user1TokenAccount));
 
       await program.methods
-        .joinUser(sessionId, 0)
+        .joinUser(sessionIndex, 0)
         .accounts({
           user: user1.publicKey,
           gameServer: gameServer.publicKey,
@@ -251,7 +254,7 @@ describe("Join User Tests", () => {
     console.log("\nJoining user2 to team...");
     try {
       await program.methods
-        .joinUser(sessionId, 1)
+        .joinUser(sessionIndex, 1)
         .accounts({
           user: user2.publicKey,
           gameServer: gameServer.publicKey,
@@ -8,6 +8,7 @@ import {
   generateSessionId,
   deriveGameSessionPDA,
   deriveVaultPDA,
+  nextSessionIndex,
   loadKeypair,
   setupTokenAccount,
   setupTestAccounts,
@@ -15,7 +16,8 @@ import {
   getVaultTokenAccount,
   TOKEN_ID,
   getTokenBalance,
-  printGameState
+  printGameState,
+  playerProfileAccounts
 } from "./utils";
 import { PublicKey } from "@solana/web3.js";
 import * as borsh from "borsh";
@@ -99,6 +101,7 @@ describe("Distribute Winnings Tests", () => {
   it("Successfully distributes winnings to winning team", async () => {
     console.log("\n=== Starting distribute winnings test ===");
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(1000000000); // 0.1 tokens with 9 decimals
     console.log(`Session ID: ${sessionId}`);
     console.log(`Bet amount: ${betAmount.toString()} (0.1 tokens)`);
@@ -114,7 +117,7 @@ describe("Distribute Winnings Tests", () => {
     // Create game session and join users
     console.log("\nCreating game session...");
     const tx = await program.methods
-      .createGameSession(sessionId, betAmount, { payToSpawnOneVsOne: {} })
+      .createGameSession(sessionId, betAmount, { payToSpawnOneVsOne: {} }, null, null, null, false, null)
       .accounts({
         gameServer: gameServer.publicKey,
       })
@@ -147,7 +150,7 @@ describe("Distribute Winnings Tests", () => {
     // Join users
     console.log("\nJoining user 1...");
     const joinUser1Tx = await program.methods
-      .joinUser(sessionId, 0)
+      .joinUser(sessionIndex, 0)
       .accounts({
         user: user1.publicKey,
         gameServer: gameServer.publicKey,
@@ -158,7 +161,7 @@ describe("Distribute Winnings Tests", () => {
 
     console.log("\nJoining user 2...");
     const joinUser2Tx = await program.methods
-      .joinUser(sessionId, 1)
+      .joinUser(sessionIndex, 1)
       .accounts({
         user: user2.publicKey,
         gameServer: gameServer.publicKey,
@@ -171,7 +174,7 @@ describe("Distribute Winnings Tests", () => {
     console.log("user2 balance after joining: ", await getTokenBalance(provider.connection, user2TokenAccount));
     console.log("vault token account: and balance", vaultTokenAccount, await getTokenBalance(provider.connection, new PublicKey(vaultTokenAccount)));
 
-    const [gameSessionPda] = deriveGameSessionPDA(program.programId, sessionId);
+    const [gameSessionPda] = deriveGameSessionPDA(program.programId, gameServer.publicKey, sessionIndex);
     console.log("Game session PDA:", gameSessionPda.toString());
     const account = await program.account.gameSession.fetch(gameSessionPda);
 
@@ -180,7 +183,7 @@ describe("Distribute Winnings Tests", () => {
     await printGameState(gameStateJoin, "Game stats after join:", vaultTokenAccount, provider.connection);
 
 
-    const killTx1 = await program.methods.recordKill(sessionId, 0, user1.publicKey, 1, user2.publicKey).accounts({
+    const killTx1 = await program.methods.recordKill(sessionIndex, 0, user1.publicKey, 1, user2.publicKey).accounts({
       gameServer: gameServer.publicKey,
     })
     .signers([gameServer])
@@ -191,7 +194,7 @@ describe("Distribute Winnings Tests", () => {
     await printGameState(gameState1, "Game stats after kill 1", vaultTokenAccount, provider.connection);
 
     for (let i = 1; i < 10; i++) {
-        const killTx2 = await program.methods.recordKill(sessionId, 0, user1.publicKey, 1, user2.publicKey).accounts({
+        const killTx2 = await program.methods.recordKill(sessionIndex, 0, user1.publicKey, 1, user2.publicKey).accounts({
             gameServer: gameServer.publicKey,
         })
         .signers([gameServer])
@@ -200,7 +203,7 @@ describe("Distribute Winnings Tests", () => {
     const gameState2 = await program.account.gameSession.fetch(gameSessionPda);
     await printGameState(gameState2, "Game stats after kill 10", vaultTokenAccount, provider.connection);
 
-    const txspawn1 = await program.methods.payToSpawn(sessionId, 1).accounts({
+    const txspawn1 = await program.methods.payToSpawn(sessionIndex, 1).accounts({
         user: user2.publicKey,
         gameServer: gameServer.publicKey,
         userTokenAccount: user2TokenAccount,
@@ -213,7 +216,7 @@ describe("Distribute Winnings Tests", () => {
     await printGameState(gameStateSpawn1, "Player pays to spawn, Game stats after that:", vaultTokenAccount, provider.connection);
 
     for (let i = 0; i < 10; i++) {
-        const killTx2 = await program.methods.recordKill(sessionId, 0, user1.publicKey, 1, user2.publicKey).accounts({
+        const killTx2 = await program.methods.recordKill(sessionIndex, 0, user1.publicKey, 1, user2.publicKey).accounts({
             gameServer: gameServer.publicKey,
         })
         .signers([gameServer])
@@ -231,9 +234,10 @@ describe("Distribute Winnings Tests", () => {
     // Distribute winnings
     console.log("\nDistributing winnings...");
     const txDistribute = await program.methods
-      .distributeWinnings(sessionId, 0)
+      .distributeWinnings(sessionIndex, 0)
       .accounts({
         gameServer: gameServer.publicKey,
+        remainderTokenAccount: null,
       })
       .remainingAccounts([
         {
@@ -256,6 +260,7 @@ describe("Distribute Winnings Tests", () => {
           isSigner: false,
           isWritable: true,
         },
+        ...playerProfileAccounts(program.programId, [user1.publicKey, user2.publicKey]),
       ])
       .signers([gameServer])
       .rpc(confirmOptions);
@@ -8,6 +8,7 @@ import {
   generateSessionId,
   deriveGameSessionPDA,
   deriveVaultPDA,
+  nextSessionIndex,
   loadKeypair,
   setupTokenAccount,
   setupTestAccounts,
@@ -97,6 +98,7 @@ describe("Distribute Winnings Tests", () => {
   it("Successfully refunds wager", async () => {
     console.log("\n=== Starting Refund Wager test ===");
     const sessionId = generateSessionId();
+    const sessionIndex = await nextSessionIndex(program, gameServer.publicKey);
     const betAmount = new BN(100000000); // 0.1 tokens with 9 decimals
     console.log(`Session ID: ${sessionId}`);
     console.log(`Bet amount: ${betAmount.toString()} (0.1 tokens)`);
@@ -114,7 +116,7 @@ describe("Distribute Winnings Tests", () => {
     // Create game session and join users
     console.log("\nCreating game session...");
     const tx = await program.methods
-      .createGameSession(sessionId, betAmount, { winnerTakesAllThreeVsThree: {} })
+      .createGameSession(sessionId, betAmount, { winnerTakesAllThreeVsThree: {} }, null, null, null, false, null)
       .accounts({
         gameServer: gameServer.publicKey,
       })
@@ -151,7 +153,7 @@ describe("Distribute Winnings Tests", () => {
     // Join users
     console.log("\nJoining user 1...");
     const joinUser1Tx = await program.methods
-      .joinUser(sessionId, 0)
+      .joinUser(sessionIndex, 0)
       .accounts({
         user: user1.publicKey,
         gameServer: gameServer.publicKey,
@@ -163,7 +165,7 @@ describe("Distribute Winnings Tests", () => {
 
     console.log("\nJoining user 2...");
     const joinUser2Tx = await program.methods
-      .joinUser(sessionId, 1)
+      .joinUser(sessionIndex, 1)
       .accounts({
         user: user2.publicKey,
         gameServer: gameServer.publicKey,
@@ -175,7 +177,7 @@ describe("Distribute Winnings Tests", () => {
 
     console.log("\nJoining user 3...");
     const joinUser3Tx = await program.methods
-      .joinUser(sessionId, 0)
+      .joinUser(sessionIndex, 0)
       .accounts({
         user: user3.publicKey,
         gameServer: gameServer.publicKey,
@@ -221,7 +223,7 @@ describe("Distribute Winnings Tests", () => {
     ]
 
     const refundWagerTx = await program.methods
-      .refundWager(sessionId)
+      .refundWager(sessionIndex)
       .accounts({
         gameServer: gameServer.publicKey,
       })
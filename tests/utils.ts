@@ -15,8 +15,9 @@ export function randomIntFromInterval(min: number, max: number): number {
   return Math.floor(Math.random() * (max - min + 1) + min);
 }
 
+// Human-readable session ids are at most 10 bytes long
 export function generateSessionId(): string {
-  return "game" + String(randomIntFromInterval(0, 1000000000));
+  return "game" + String(randomIntFromInterval(0, 999999));
 }
 
 export async function getBalance(connection: anchor.web3.Connection, publicKey: PublicKey): Promise<number> {
@@ -41,20 +42,71 @@ export async function createAndFundAccount(
   return account;
 }
 
-export function deriveGameSessionPDA(programId: PublicKey, sessionId: string): [PublicKey, number] {
+export function deriveGlobalConfigPDA(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("global_config")], programId);
+}
+
+// Creates the global config once, with the provider wallet (the upgrade authority) as admin
+export async function ensureGlobalConfig(program: anchor.Program<any>, admin: PublicKey): Promise<PublicKey> {
+  const [configPda] = deriveGlobalConfigPDA(program.programId);
+  const existing = await program.account.globalConfig.fetchNullable(configPda);
+  if (!existing) {
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+    await program.methods
+      .initializeConfig(admin, { minBet: new BN(1), maxBet: new BN("18446744073709551615") })
+      .accounts({ admin, program: program.programId, programData })
+      .rpc();
+  }
+  return configPda;
+}
+
+export function deriveSessionCounterPDA(programId: PublicKey, authority: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("session_counter"), authority.toBuffer()],
+    programId
+  );
+}
+
+// Index the next session created by `authority` will get
+export async function nextSessionIndex(program: anchor.Program<any>, authority: PublicKey): Promise<BN> {
+  const [counterPda] = deriveSessionCounterPDA(program.programId, authority);
+  const counter = await program.account.sessionCounter.fetchNullable(counterPda);
+  return counter ? counter.nextIndex : new BN(0);
+}
+
+export function deriveGameSessionPDA(programId: PublicKey, authority: PublicKey, sessionIndex: BN): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("game_session"), authority.toBuffer(), sessionIndex.toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+}
+
+export function deriveVaultPDA(programId: PublicKey, authority: PublicKey, sessionIndex: BN): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("game_session"), Buffer.from(sessionId)],
+    [Buffer.from("vault"), authority.toBuffer(), sessionIndex.toArrayLike(Buffer, "le", 8)],
     programId
   );
 }
 
-export function deriveVaultPDA(programId: PublicKey, gameSessionPda: PublicKey): [PublicKey, number] {
+export function derivePlayerProfilePDA(programId: PublicKey, player: PublicKey): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("vault"), gameSessionPda.toBuffer()],
+    [Buffer.from("player_profile"), player.toBuffer()],
     programId
   );
 }
 
+// Profiles trail the payout accounts of distributeWinnings, team A slots first then team B
+export function playerProfileAccounts(programId: PublicKey, players: PublicKey[]) {
+  return players.map((player) => ({
+    pubkey: derivePlayerProfilePDA(programId, player)[0],
+    isSigner: false,
+    isWritable: true,
+  }));
+}
+
 export function loadKeypair(path: string): Keypair {
   const data = JSON.parse(readFileSync(path, 'utf-8'));
   return anchor.web3.Keypair.fromSecretKey(new Uint8Array(data));